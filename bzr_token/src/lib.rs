@@ -0,0 +1,203 @@
+// 🏛️ PROJECT BAZAAR | BZR TOKEN
+// SEP-41 token for the Bazaar reward currency. TrustContract is the admin and only minter.
+
+#![no_std]
+use soroban_sdk::{contract, contractevent, contractimpl, contracttype, token, Address, Env, MuxedAddress, String};
+
+// ============================================================
+// 📦 DATA STRUCTURES
+// ============================================================
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Allowance {
+    pub amount: i128,
+    pub expiration_ledger: u32,
+}
+
+#[contracttype]
+pub enum DataKey {
+    Admin,
+    Balance(Address),
+    Allowance(Address, Address), // (from, spender)
+}
+
+const DECIMALS: u32 = 0; // BZR is only ever earned in whole units
+
+// ============================================================
+// 📣 EVENTS (SEP-41 topics)
+// ============================================================
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone)]
+pub struct Mint {
+    #[topic]
+    pub to: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct Transfer {
+    #[topic]
+    pub from: Address,
+    #[topic]
+    pub to: Address,
+    pub to_muxed_id: Option<u64>,
+    pub amount: i128,
+}
+
+#[contractevent(data_format = "vec")]
+#[derive(Clone)]
+pub struct Approve {
+    #[topic]
+    pub from: Address,
+    #[topic]
+    pub spender: Address,
+    pub amount: i128,
+    pub expiration_ledger: u32,
+}
+
+#[contractevent(data_format = "single-value")]
+#[derive(Clone)]
+pub struct Burn {
+    #[topic]
+    pub from: Address,
+    pub amount: i128,
+}
+
+// ============================================================
+// 🪙 CONTRACT
+// ============================================================
+
+#[contract]
+pub struct BzrToken;
+
+#[contractimpl]
+impl BzrToken {
+    /// `admin` is the TrustContract that mints rewards.
+    pub fn init(env: Env, admin: Address) {
+        if env.storage().instance().has(&DataKey::Admin) { panic!("Already initialized"); }
+        env.storage().instance().set(&DataKey::Admin, &admin);
+    }
+
+    pub fn admin(env: Env) -> Address {
+        env.storage().instance().get(&DataKey::Admin).expect("Not initialized")
+    }
+
+    pub fn set_admin(env: Env, new_admin: Address) {
+        Self::admin(env.clone()).require_auth();
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+    }
+
+    pub fn mint(env: Env, to: Address, amount: i128) {
+        Self::admin(env.clone()).require_auth();
+        require_positive(amount);
+        credit(&env, &to, amount);
+        Mint { to, amount }.publish(&env);
+    }
+}
+
+#[contractimpl]
+impl token::TokenInterface for BzrToken {
+    fn allowance(env: Env, from: Address, spender: Address) -> i128 {
+        read_allowance(&env, &from, &spender).amount
+    }
+
+    fn approve(env: Env, from: Address, spender: Address, amount: i128, expiration_ledger: u32) {
+        from.require_auth();
+        if amount < 0 { panic!("Amount must not be negative"); }
+        if amount > 0 && expiration_ledger < env.ledger().sequence() { panic!("Expiration is in the past"); }
+        let key = DataKey::Allowance(from.clone(), spender.clone());
+        env.storage().persistent().set(&key, &Allowance { amount, expiration_ledger });
+        Approve { from, spender, amount, expiration_ledger }.publish(&env);
+    }
+
+    fn balance(env: Env, id: Address) -> i128 {
+        env.storage().persistent().get(&DataKey::Balance(id)).unwrap_or(0)
+    }
+
+    fn transfer(env: Env, from: Address, to: MuxedAddress, amount: i128) {
+        from.require_auth();
+        require_positive(amount);
+        debit(&env, &from, amount);
+        credit(&env, &to.address(), amount);
+        Transfer { from, to: to.address(), to_muxed_id: to.id(), amount }.publish(&env);
+    }
+
+    fn transfer_from(env: Env, spender: Address, from: Address, to: Address, amount: i128) {
+        spender.require_auth();
+        require_positive(amount);
+        spend_allowance(&env, &from, &spender, amount);
+        debit(&env, &from, amount);
+        credit(&env, &to, amount);
+        Transfer { from, to, to_muxed_id: None, amount }.publish(&env);
+    }
+
+    fn burn(env: Env, from: Address, amount: i128) {
+        from.require_auth();
+        require_positive(amount);
+        debit(&env, &from, amount);
+        Burn { from, amount }.publish(&env);
+    }
+
+    fn burn_from(env: Env, spender: Address, from: Address, amount: i128) {
+        spender.require_auth();
+        require_positive(amount);
+        spend_allowance(&env, &from, &spender, amount);
+        debit(&env, &from, amount);
+        Burn { from, amount }.publish(&env);
+    }
+
+    fn decimals(_env: Env) -> u32 {
+        DECIMALS
+    }
+
+    fn name(env: Env) -> String {
+        String::from_str(&env, "Bazaar")
+    }
+
+    fn symbol(env: Env) -> String {
+        String::from_str(&env, "BZR")
+    }
+}
+
+// ============================================================
+// 🔧 HELPERS
+// ============================================================
+
+fn require_positive(amount: i128) {
+    if amount <= 0 { panic!("Amount must be positive"); }
+}
+
+fn credit(env: &Env, id: &Address, amount: i128) {
+    let key = DataKey::Balance(id.clone());
+    let balance: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+    env.storage().persistent().set(&key, &balance.checked_add(amount).expect("Balance overflow"));
+}
+
+fn debit(env: &Env, id: &Address, amount: i128) {
+    let key = DataKey::Balance(id.clone());
+    let balance: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+    if balance < amount { panic!("Insufficient balance"); }
+    env.storage().persistent().set(&key, &(balance - amount));
+}
+
+/// Expired allowances read as zero.
+fn read_allowance(env: &Env, from: &Address, spender: &Address) -> Allowance {
+    let allowance: Option<Allowance> = env.storage().persistent().get(&DataKey::Allowance(from.clone(), spender.clone()));
+    match allowance {
+        Some(a) if a.expiration_ledger >= env.ledger().sequence() => a,
+        _ => Allowance { amount: 0, expiration_ledger: 0 },
+    }
+}
+
+fn spend_allowance(env: &Env, from: &Address, spender: &Address, amount: i128) {
+    let mut allowance = read_allowance(env, from, spender);
+    if allowance.amount < amount { panic!("Insufficient allowance"); }
+    allowance.amount -= amount;
+    env.storage().persistent().set(&DataKey::Allowance(from.clone(), spender.clone()), &allowance);
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,49 @@
+use super::*;
+use soroban_sdk::testutils::Address as _;
+
+fn create_token(env: &Env, admin: &Address) -> BzrTokenClient<'static> {
+    let client = BzrTokenClient::new(env, &env.register(BzrToken, ()));
+    client.init(admin);
+    client
+}
+
+#[test]
+fn test_mint_and_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let bzr = create_token(&env, &admin);
+
+    bzr.mint(&alice, &10);
+    bzr.transfer(&alice, &bob, &3);
+
+    assert_eq!(bzr.balance(&alice), 7);
+    assert_eq!(bzr.balance(&bob), 3);
+    assert_eq!(bzr.symbol(), String::from_str(&env, "BZR"));
+    assert_eq!(bzr.decimals(), 0);
+}
+
+#[test]
+fn test_allowance_and_burn() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let shop = Address::generate(&env);
+    let bzr = create_token(&env, &admin);
+
+    bzr.mint(&alice, &10);
+    bzr.approve(&alice, &shop, &6, &100);
+    bzr.transfer_from(&shop, &alice, &shop, &4);
+    bzr.burn_from(&shop, &alice, &2);
+
+    assert_eq!(bzr.allowance(&alice, &shop), 0);
+    assert_eq!(bzr.balance(&alice), 4);
+    assert_eq!(bzr.balance(&shop), 4);
+    assert!(bzr.try_transfer_from(&shop, &alice, &shop, &1).is_err());
+
+    bzr.burn(&alice, &4);
+    assert_eq!(bzr.balance(&alice), 0);
+}
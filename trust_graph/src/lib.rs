@@ -0,0 +1,141 @@
+// 🏛️ PROJECT BAZAAR | TRUST GRAPH (off-chain)
+// EigenTrust propagation over the exported vouch graph. The oracle runs this
+// and commits the scaled results to `TrustContract::commit_graph_scores`.
+
+use std::collections::BTreeMap;
+
+// ============================================================
+// 📦 DATA STRUCTURES
+// ============================================================
+
+/// One vouch as exported from the contract's `Vouched` events / `get_vouch` records.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VouchEdge {
+    pub voucher: String,
+    pub target: String,
+    pub weight: u32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EigenTrustConfig {
+    /// Share of every round that restarts at the pre-trusted set (the damping factor).
+    pub alpha: f64,
+    /// Iteration stops once the L1 change between rounds drops below this.
+    pub epsilon: f64,
+    pub max_iterations: u32,
+}
+
+impl Default for EigenTrustConfig {
+    fn default() -> Self {
+        EigenTrustConfig { alpha: 0.15, epsilon: 1e-9, max_iterations: 100 }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct VouchGraph {
+    ids: Vec<String>,
+    index: BTreeMap<String, usize>,
+    edges: Vec<BTreeMap<usize, u32>>, // voucher -> target -> summed weight
+}
+
+// ============================================================
+// 🔗 GRAPH
+// ============================================================
+
+impl VouchGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_edges(edges: &[VouchEdge]) -> Self {
+        let mut graph = Self::new();
+        for edge in edges {
+            graph.add_vouch(&edge.voucher, &edge.target, edge.weight);
+        }
+        graph
+    }
+
+    /// Self-vouches and zero weights are ignored, matching what the contract accepts.
+    pub fn add_vouch(&mut self, voucher: &str, target: &str, weight: u32) {
+        let from = self.node(voucher);
+        let to = self.node(target);
+        if from == to || weight == 0 { return; }
+        *self.edges[from].entry(to).or_insert(0) += weight;
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    fn node(&mut self, id: &str) -> usize {
+        if let Some(&i) = self.index.get(id) { return i; }
+        let i = self.ids.len();
+        self.ids.push(id.to_string());
+        self.index.insert(id.to_string(), i);
+        self.edges.push(BTreeMap::new());
+        i
+    }
+}
+
+// ============================================================
+// 🧮 EIGENTRUST
+// ============================================================
+
+/// Global trust per merchant; the scores sum to 1.
+///
+/// `pre_trusted` anchors the walk (e.g. Seal holders). Trust only reaches a
+/// vouch ring through a path from that set, so rings vouching for each other gain
+/// nothing on their own. With no pre-trusted merchants every node is an anchor.
+pub fn compute(graph: &VouchGraph, pre_trusted: &[String], config: &EigenTrustConfig) -> BTreeMap<String, f64> {
+    let n = graph.len();
+    if n == 0 { return BTreeMap::new(); }
+
+    let anchors: Vec<usize> = pre_trusted.iter().filter_map(|id| graph.index.get(id).copied()).collect();
+    let mut p = vec![0.0; n];
+    if anchors.is_empty() {
+        p.iter_mut().for_each(|v| *v = 1.0 / n as f64);
+    } else {
+        for &i in &anchors { p[i] += 1.0 / anchors.len() as f64; }
+    }
+
+    let mut t = p.clone();
+    for _ in 0..config.max_iterations {
+        let mut next = vec![0.0; n];
+        for (from, targets) in graph.edges.iter().enumerate() {
+            let total: u64 = targets.values().map(|&w| w as u64).sum();
+            if total == 0 {
+                // Merchants who vouch for nobody hand their trust back to the anchors
+                for (i, share) in p.iter().enumerate() { next[i] += t[from] * share; }
+                continue;
+            }
+            for (&to, &weight) in targets {
+                next[to] += t[from] * weight as f64 / total as f64;
+            }
+        }
+        for i in 0..n {
+            next[i] = (1.0 - config.alpha) * next[i] + config.alpha * p[i];
+        }
+        let delta: f64 = next.iter().zip(&t).map(|(a, b)| (a - b).abs()).sum();
+        t = next;
+        if delta < config.epsilon { break; }
+    }
+
+    graph.ids.iter().cloned().zip(t).collect()
+}
+
+/// Maps global trust onto `0..=ceiling` with the best-trusted merchant at the ceiling,
+/// ready to be committed on-chain.
+pub fn scale_scores(scores: &BTreeMap<String, f64>, ceiling: i32) -> BTreeMap<String, i32> {
+    let max = scores.values().cloned().fold(0.0, f64::max);
+    scores.iter().map(|(id, &score)| {
+        let scaled = if max > 0.0 { (score / max * ceiling as f64).round() as i32 } else { 0 };
+        (id.clone(), scaled)
+    }).collect()
+}
+
+#[cfg(test)]
+mod test;
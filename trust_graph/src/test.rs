@@ -0,0 +1,45 @@
+use super::*;
+
+fn ids(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn test_scores_sum_to_one() {
+    let mut graph = VouchGraph::new();
+    graph.add_vouch("a", "b", 1);
+    graph.add_vouch("b", "c", 2);
+    graph.add_vouch("c", "a", 1);
+    let scores = compute(&graph, &[], &EigenTrustConfig::default());
+    let total: f64 = scores.values().sum();
+    assert!((total - 1.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_vouch_ring_gains_nothing_without_anchor() {
+    let mut graph = VouchGraph::new();
+    graph.add_vouch("seal", "honest", 1);
+    graph.add_vouch("honest", "seal", 1);
+    // A ring of fresh accounts vouching heavily for each other
+    graph.add_vouch("ring1", "ring2", 10);
+    graph.add_vouch("ring2", "ring3", 10);
+    graph.add_vouch("ring3", "ring1", 10);
+
+    let scores = compute(&graph, &ids(&["seal"]), &EigenTrustConfig::default());
+    assert!(scores["honest"] > 0.3);
+    assert!(scores["ring1"] < 1e-9);
+
+    let scaled = scale_scores(&scores, 2049);
+    assert_eq!(scaled.values().max(), Some(&2049));
+    assert_eq!(scaled["ring1"], 0);
+}
+
+#[test]
+fn test_self_vouch_is_ignored() {
+    let graph = VouchGraph::from_edges(&[
+        VouchEdge { voucher: "a".into(), target: "a".into(), weight: 5 },
+        VouchEdge { voucher: "a".into(), target: "b".into(), weight: 1 },
+    ]);
+    let scores = compute(&graph, &ids(&["a"]), &EigenTrustConfig::default());
+    assert!(scores["b"] > scores["a"] * 0.5);
+}
@@ -1,8 +1,8 @@
 // 🏛️ PROJECT BAZAAR | SMART CONTRACT v3.1 (Stabilized)
-// Includes: Academy Trust Score, Legacy Vault, Medical Emergency, and Panic Protocol.
+// Includes: Academy Trust Score, Legacy Vault, Medical Emergency, Panic Protocol, Exile Protocol, Trust Graph Oracle, BZR rewards, Badge Shop, and Crowdfunding.
 
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, String, Symbol, Vec};
+use soroban_sdk::{contract, contractevent, contractimpl, contracttype, symbol_short, token, Address, Env, Map, String, Symbol, Vec};
 
 // ============================================================
 // 📦 DATA STRUCTURES
@@ -11,13 +11,140 @@ use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, String, Sy
 #[contracttype]
 #[derive(Clone)]
 pub struct Merchant {
-    pub trust_score: u32,
+    pub trust_score: i32, // Trust Variance, always within TRUST_FLOOR..=TRUST_CEILING
     pub bond_staked: bool,
-    pub bzr_balance: i128,
+    pub bond_token: Option<Address>,
+    pub bond_amount: i128, // held in escrow by the contract while bonded
+    pub bond_unlocks_at: u64,
     pub badges: Vec<Symbol>,
     pub is_disputed: bool,
     pub nickname: Symbol,
     pub messages: Vec<Message>,
+    pub status: MerchantStatus,
+    pub last_activity: u64, // decay of a positive score is measured from here
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MerchantStatus {
+    Active,
+    Exiled,
+}
+
+/// Exile Protocol floors: a merchant whose score or escrowed bond drops below them is exiled.
+#[contracttype]
+#[derive(Clone)]
+pub struct ExileConfig {
+    pub trust_floor: i32,
+    pub bond_floor: i128,
+}
+
+/// The 7-day Poverty Observation window, started once by a merchant's first stake.
+#[contracttype]
+#[derive(Clone)]
+pub struct PovertyObservation {
+    pub start_time: u64,
+    pub is_active: bool,
+}
+
+impl PovertyObservation {
+    pub fn start_observation(&mut self, now: u64) {
+        self.start_time = now;
+        self.is_active = true;
+    }
+
+    /// True once the full window has elapsed; closes the observation at that point.
+    pub fn verify_window(&mut self, now: u64) -> bool {
+        if now < self.start_time + OBSERVATION_WINDOW { return false; }
+        self.is_active = false;
+        true
+    }
+}
+
+/// Admin-set terms for new bonds. Existing bonds keep the terms they were staked under.
+#[contracttype]
+#[derive(Clone)]
+pub struct BondConfig {
+    pub token: Address,
+    pub amount: i128,
+    pub lock_period: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct VouchRecord {
+    pub voucher: Address,
+    pub target: Address,
+    pub weight: i32,
+    pub timestamp: u64,
+}
+
+/// Audit entry for an admin-imposed decay penalty.
+#[contracttype]
+#[derive(Clone)]
+pub struct DecayRecord {
+    pub admin: Address,
+    pub penalty: i32,
+    pub score_after: i32,
+    pub timestamp: u64,
+}
+
+/// The off-chain EigenTrust oracle (see the `trust_graph` crate) and how much of
+/// `get_trust` its score accounts for.
+#[contracttype]
+#[derive(Clone)]
+pub struct OracleConfig {
+    pub oracle: Address,
+    pub blend_bps: u32,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct GraphScore {
+    pub score: i32,
+    pub epoch: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MintReason {
+    Vouch,
+    Referral,
+    Lottery,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct SupplyStats {
+    pub cap: i128,
+    pub total_minted: i128,
+    pub epoch: u64,
+    pub epoch_budget: i128,
+    pub epoch_minted: i128,
+    pub minted_by_reason: Map<MintReason, i128>,
+}
+
+/// A badge the admin sells for BZR. `stock: None` is unlimited and `duration: None` never expires.
+#[contracttype]
+#[derive(Clone)]
+pub struct BadgeListing {
+    pub price: i128,
+    pub stock: Option<u32>,
+    pub duration: Option<u64>,
+    pub min_trust: Option<i32>,
+    pub burn: bool, // false routes the payment to the treasury
+}
+
+/// A BZR fundraiser. Contributions stay in the contract until the beneficiary
+/// claims a met goal, or are refunded once the deadline passes short of it.
+#[contracttype]
+#[derive(Clone)]
+pub struct Campaign {
+    pub beneficiary: Address,
+    pub goal: i128,
+    pub deadline: u64,
+    pub raised: i128,
+    pub claimed: bool,
 }
 
 #[contracttype]
@@ -28,30 +155,343 @@ pub struct Message {
     pub timestamp: u64,
 }
 
+#[contracttype]
+#[derive(Clone)]
+pub struct HeirShare {
+    pub heir: Address,
+    pub share_bps: u32, // 10_000 = 100%
+}
+
+/// Releases each heir's share in equal tranches, the first one at settlement.
+/// A single tranche pays the whole share out at once.
+#[contracttype]
+#[derive(Clone)]
+pub struct ReleaseSchedule {
+    pub tranches: u32,
+    pub interval: u64, // seconds between tranches
+}
+
 #[contracttype]
 #[derive(Clone)]
 pub struct LegacyVault {
-    pub heir: Option<Address>,
+    pub heirs: Vec<HeirShare>,
     pub last_heartbeat: u64,
+    pub deadman_period: u64,
     pub is_locked: bool,
     pub is_frozen: bool,
+    pub assets: Map<Address, i128>, // token contract -> amount still held
+    pub is_settled: bool,
+    pub settled_at: u64,
+    pub estate: Map<Address, i128>, // snapshot of `assets` taken at settlement
+    pub release_schedule: ReleaseSchedule,
+    pub medical_caps: Map<Address, i128>, // token -> max medical emergencies may release in total
+    pub medical_released: Map<Address, i128>, // token -> released against the cap since it was set
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum VaultChange {
+    Heirs(Vec<HeirShare>),
+    DeadmanPeriod(u64),
+    ReleaseSchedule(ReleaseSchedule),
+    Withdrawal(Address, i128), // (token, amount)
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct PendingVaultChange {
+    pub change: VaultChange,
+    pub effective_at: u64,
+}
+
+/// M-of-N rules for a user's Security Circle. Thresholds never exceed the current circle size.
+#[contracttype]
+#[derive(Clone)]
+pub struct CircleConfig {
+    pub max_size: u32,
+    pub medical_threshold: u32,
+    pub panic_threshold: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EmergencyStatus {
+    Active,
+    Unlocked,
+    Cancelled,
+    Expired,
+    Resolved,
 }
 
 #[contracttype]
 #[derive(Clone)]
 pub struct MedicalEmergency {
     pub target_user: Address,
-    pub votes_collected: u32,
-    pub is_unlocked: bool,
+    pub declared_by: Address,
+    pub voters: Vec<Address>,
+    pub status: EmergencyStatus,
+    pub declared_at: u64,
+    pub expires_at: u64, // voting closes if the threshold isn't met by then
+    pub payee: Address, // e.g. the hospital
+    pub token: Address,
+    pub requested: i128,
+    pub released: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PanicStatus {
+    Active,
+    ClearedByOwner,
+    LiftedByCircle,
+}
+
+/// One triggering of the Panic Protocol, from the freeze until the owner or the circle clears it.
+#[contracttype]
+#[derive(Clone)]
+pub struct PanicEvent {
+    pub triggered_at: u64,
+    pub triggered_by: Vec<Address>,
+    pub previous_heartbeat: u64, // restored if the circle lifts a mistaken panic
+    pub status: PanicStatus,
+    pub lift_voters: Vec<Address>,
+    pub resolved_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct MedicalPayout {
+    pub payee: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub timestamp: u64,
 }
 
+const BASIS_POINTS: u32 = 10_000;
+const DEFAULT_DEADMAN_PERIOD: u64 = 15_552_000; // 180 Days
+const MIN_DEADMAN_PERIOD: u64 = 2_592_000; // 30 Days
+const VAULT_CHANGE_DELAY: u64 = 604_800; // 7 Days for the circle to veto
+const MAX_HEARTBEAT_DELEGATES: u32 = 5;
+const MAX_RELEASE_TRANCHES: u32 = 120;
+const DEFAULT_CIRCLE_SIZE: u32 = 5;
+const DEFAULT_CIRCLE_THRESHOLD: u32 = 3;
+const MAX_CIRCLE_SIZE: u32 = 15;
+const EMERGENCY_WINDOW: u64 = 259_200; // 72 Hours to collect witness votes
+const MAX_EMERGENCY_HISTORY: u32 = 20;
+const PANIC_HEIR_DELAY: u64 = 604_800; // 7 Days until heirs may claim a panicked vault
+const PANIC_COOLDOWN: u64 = 86_400; // 24 Hours before the circle may panic again
+const MAX_PANIC_HISTORY: u32 = 20;
+const TRUST_FLOOR: i32 = -1987;
+const TRUST_CEILING: i32 = 2049;
+const STAKE_BONUS: i32 = 10;
+const VOUCH_WEIGHT_STEP: i32 = 200; // every 200 points of the voucher's own score adds 1 to their vouch
+const MAX_VOUCH_WEIGHT: i32 = 10;
+const MAX_VOUCHERS: u32 = 500; // keeps Vouchers(target) well inside the ledger entry size limit
+const SLASH_BATCH: u32 = 10; // vouchers slashed per call, to stay inside the transaction footprint
+const VOUCH_SLASH_FACTOR: i32 = 2; // vouchers lose twice the weight they lent
+const DISPUTE_PENALTY: i32 = 50;
+const DEFAULT_EXILE_TRUST_FLOOR: i32 = -20;
+const MAX_INBOX: u32 = 50;
+const SEAL_THRESHOLD: i32 = 2049;
+const SEAL_HYSTERESIS: i32 = 100; // the Seal is only revoked below 1949 so it doesn't flicker
+const OBSERVATION_WINDOW: u64 = 604_800; // 7 Days
+const DEFAULT_DECAY_HALF_LIFE: u64 = 7_776_000; // 90 Days of inactivity halves a positive score
+const DECAY_PENALTY: i32 = 3;
+const MAX_DECAY_HISTORY: u32 = 20;
+const VOUCH_REWARD: i128 = 5; // BZR minted to the voucher
+const MAX_BZR_SUPPLY: i128 = 5_000_000;
+const EMISSION_EPOCH: u64 = 604_800; // 7 Days
+const INITIAL_EPOCH_BUDGET: i128 = 50_000;
+const EMISSION_HALVING_EPOCHS: u64 = 52; // the weekly budget halves every year
+const REFERRAL_REWARD: i128 = 10;
+const MAX_REFERRALS_PER_EPOCH: u32 = 5;
+
 #[contracttype]
 pub enum DataKey {
+    Admin,
+    BondConfig,
+    ExileConfig,
+    DecayHalfLife,
+    DecayHistory(Address),
+    OracleConfig,
+    GraphEpoch,
+    GraphScore(Address),
+    BzrToken,
+    EmissionStart,
+    TotalMinted,
+    EpochMinted(u64),
+    MintedFor(MintReason),
+    Referrer(Address), // referee -> referrer
+    Referrals(Address), // referrer -> referees, in signup order
+    ReferralPaid(Address),
+    ReferralRewards(Address, u64), // (referrer, emission epoch) -> rewards paid
+    Treasury,
+    BadgeCatalog,
+    Badge(Symbol),
+    BadgeExpiry(Address, Symbol), // (owner, badge) -> expiry timestamp
+    CampaignCount,
+    Campaign(u32),
+    Contributors(u32),
+    Contribution(u32, Address), // (campaign, contributor) -> amount not yet refunded
+    Observation(Address),
+    Vouch(Address, Address), // (voucher, target)
+    Vouchers(Address), // target -> everyone with a live vouch for them
+    RevokedVouch(Address, Address), // (voucher, target) tombstone: a revoked pair can't vouch again
+    PendingSlash(Address), // target -> index into Vouchers(target) of the next voucher to slash
     Merchant(Address),
     Vault(Address),
     Witnesses(Address),
     Emergency(Address),
     PanicVotes(Address),
+    PendingVaultChange(Address),
+    PendingRemovals(Address), // owner -> witness -> removal effective_at
+    HeartbeatDelegates(Address),
+    HeirClaimed(Address, Address), // (owner, heir) -> token -> amount claimed
+    CircleConfig(Address),
+    EmergencyHistory(Address),
+    MedicalPayouts(Address),
+    Panic(Address),
+    PanicHistory(Address),
+    WitnessInvites(Address),
+    JoinedCircles(Address), // witness -> owners whose circle they accepted
+}
+
+// ============================================================
+// 📣 EVENTS
+// ============================================================
+
+/// Published so the owner's Security Circle can review (and veto) the change during the delay.
+#[contractevent]
+#[derive(Clone)]
+pub struct VaultChangeProposed {
+    #[topic]
+    pub owner: Address,
+    pub change: VaultChange,
+    pub effective_at: u64,
+    pub witnesses: Vec<Address>,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct Vouched {
+    #[topic]
+    pub voucher: Address,
+    #[topic]
+    pub target: Address,
+    pub weight: i32,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct VouchRevoked {
+    #[topic]
+    pub voucher: Address,
+    #[topic]
+    pub target: Address,
+    pub weight: i32,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct TrustDecayed {
+    #[topic]
+    pub merchant: Address,
+    pub admin: Address,
+    pub penalty: i32,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct GraphScoresCommitted {
+    #[topic]
+    pub epoch: u64,
+    pub count: u32,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct BadgePurchased {
+    #[topic]
+    pub buyer: Address,
+    #[topic]
+    pub badge: Symbol,
+    pub price: i128,
+    pub burned: bool,
+    pub expires_at: u64, // 0 = never
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct CampaignCreated {
+    #[topic]
+    pub id: u32,
+    #[topic]
+    pub beneficiary: Address,
+    pub goal: i128,
+    pub deadline: u64,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct MerchantExiled {
+    #[topic]
+    pub merchant: Address,
+    pub trust_score: i32,
+    pub bond_amount: i128,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct MerchantReinstated {
+    #[topic]
+    pub merchant: Address,
+    pub admin: Address,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct SealChanged {
+    #[topic]
+    pub merchant: Address,
+    pub awarded: bool,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct WitnessInvited {
+    #[topic]
+    pub owner: Address,
+    #[topic]
+    pub witness: Address,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct MedicalFundsReleased {
+    #[topic]
+    pub owner: Address,
+    #[topic]
+    pub payee: Address,
+    pub token: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+#[derive(Clone)]
+pub struct VaultChangeVetoed {
+    #[topic]
+    pub owner: Address,
+    pub witness: Address,
+}
+
+/// Published when the owner schedules a witness removal, so the circle sees it coming.
+#[contractevent]
+#[derive(Clone)]
+pub struct WitnessRemovalScheduled {
+    #[topic]
+    pub owner: Address,
+    pub witness: Address,
+    pub effective_at: u64,
 }
 
 // ============================================================
@@ -65,15 +505,62 @@ impl TrustContract {
 
     // --- FEATURE 1: LEGACY VAULT ---
 
-    pub fn create_vault(env: Env, user: Address, heir: Address) {
+    pub fn create_vault(env: Env, user: Address, heirs: Vec<HeirShare>) {
         user.require_auth();
+        check_heir_shares(&heirs);
+        let key = DataKey::Vault(user);
+        // Heirs and the deadman period only change through the time-locked update flow
+        if env.storage().persistent().has(&key) { panic!("Vault already exists"); }
         let vault = LegacyVault {
-            heir: Some(heir),
+            heirs,
             last_heartbeat: env.ledger().timestamp(),
+            deadman_period: DEFAULT_DEADMAN_PERIOD,
             is_locked: true,
             is_frozen: false,
+            assets: Map::new(&env),
+            is_settled: false,
+            settled_at: 0,
+            estate: Map::new(&env),
+            release_schedule: ReleaseSchedule { tranches: 1, interval: 0 },
+            medical_caps: Map::new(&env),
+            medical_released: Map::new(&env),
         };
-        env.storage().persistent().set(&DataKey::Vault(user), &vault);
+        env.storage().persistent().set(&key, &vault);
+    }
+
+    pub fn deposit_to_vault(env: Env, user: Address, token: Address, amount: i128) {
+        user.require_auth();
+        if amount <= 0 { panic!("Amount must be positive"); }
+        let key = DataKey::Vault(user.clone());
+        let mut vault: LegacyVault = env.storage().persistent().get(&key).expect("Vault not found");
+        if vault.is_settled { panic!("Vault already settled"); }
+
+        token::Client::new(&env, &token).transfer(&user, env.current_contract_address(), &amount);
+        let held = vault.assets.get(token.clone()).unwrap_or(0);
+        vault.assets.set(token, held + amount);
+        env.storage().persistent().set(&key, &vault);
+    }
+
+    /// Withdrawals go through the same time lock as heir changes, so the circle can veto
+    /// a stolen key emptying the vault.
+    pub fn withdraw_from_vault(env: Env, user: Address, token: Address, amount: i128) {
+        user.require_auth();
+        if amount <= 0 { panic!("Amount must be positive"); }
+        let held = Self::get_vault_balance(env.clone(), user.clone(), token.clone());
+        if held < amount { panic!("Insufficient vault balance"); }
+        propose_vault_change(&env, user, VaultChange::Withdrawal(token, amount));
+    }
+
+    /// Caps the total that medical emergencies may release from the vault in `token`.
+    /// Setting it again restarts the running total. Zero disables releases.
+    pub fn set_medical_cap(env: Env, user: Address, token: Address, cap: i128) {
+        user.require_auth();
+        if cap < 0 { panic!("Cap must not be negative"); }
+        let key = DataKey::Vault(user);
+        let mut vault: LegacyVault = env.storage().persistent().get(&key).expect("Vault not found");
+        vault.medical_caps.set(token.clone(), cap);
+        vault.medical_released.remove(token);
+        env.storage().persistent().set(&key, &vault);
     }
 
     pub fn ping_heartbeat(env: Env, user: Address) {
@@ -83,106 +570,1410 @@ impl TrustContract {
             vault.is_frozen = false; 
         }
         vault.last_heartbeat = env.ledger().timestamp();
-        env.storage().persistent().set(&DataKey::Vault(user), &vault);
+        env.storage().persistent().set(&DataKey::Vault(user.clone()), &vault);
+
+        // The owner proving they're alive ends any active panic
+        if let Some(event) = env.storage().persistent().get::<_, PanicEvent>(&DataKey::Panic(user.clone())) {
+            close_panic(&env, &user, event, PanicStatus::ClearedByOwner);
+        }
+    }
+
+    /// Registers a low-privilege key (e.g. a phone) that may only refresh `last_heartbeat`.
+    pub fn add_heartbeat_delegate(env: Env, user: Address, delegate: Address) {
+        user.require_auth();
+        if !env.storage().persistent().has(&DataKey::Vault(user.clone())) { panic!("Vault not found"); }
+        let key = DataKey::HeartbeatDelegates(user);
+        let mut delegates: Vec<Address> = env.storage().persistent().get(&key).unwrap_or(Vec::new(&env));
+        if delegates.contains(delegate.clone()) { panic!("Already a delegate"); }
+        if delegates.len() >= MAX_HEARTBEAT_DELEGATES { panic!("Max 5 delegates"); }
+        delegates.push_back(delegate);
+        env.storage().persistent().set(&key, &delegates);
+    }
+
+    pub fn revoke_heartbeat_delegate(env: Env, user: Address, delegate: Address) {
+        user.require_auth();
+        let key = DataKey::HeartbeatDelegates(user);
+        let mut delegates: Vec<Address> = env.storage().persistent().get(&key).unwrap_or(Vec::new(&env));
+        let index = delegates.first_index_of(delegate).expect("Not a delegate");
+        delegates.remove(index);
+        env.storage().persistent().set(&key, &delegates);
     }
 
-    pub fn claim_legacy(env: Env, target_user: Address) {
-        let vault: LegacyVault = env.storage().persistent().get(&DataKey::Vault(target_user.clone())).expect("Vault not found");
-        let heir = vault.heir.unwrap();
+    pub fn delegate_heartbeat(env: Env, delegate: Address, user: Address) {
+        delegate.require_auth();
+        let delegates: Vec<Address> = env.storage().persistent().get(&DataKey::HeartbeatDelegates(user.clone())).unwrap_or(Vec::new(&env));
+        if !delegates.contains(delegate) { panic!("Not a delegate"); }
+
+        let key = DataKey::Vault(user);
+        let mut vault: LegacyVault = env.storage().persistent().get(&key).expect("Vault not found");
+        // Lifting a panic freeze stays with the owner's main key
+        if vault.is_frozen { panic!("Vault is frozen"); }
+        if vault.is_settled { panic!("Vault already settled"); }
+        vault.last_heartbeat = env.ledger().timestamp();
+        env.storage().persistent().set(&key, &vault);
+    }
+
+    pub fn get_heartbeat_delegates(env: Env, user: Address) -> Vec<Address> {
+        env.storage().persistent().get(&DataKey::HeartbeatDelegates(user)).unwrap_or(Vec::new(&env))
+    }
+
+    pub fn claim_legacy(env: Env, target_user: Address, heir: Address) {
         heir.require_auth(); 
+        let key = DataKey::Vault(target_user.clone());
+        let mut vault: LegacyVault = env.storage().persistent().get(&key).expect("Vault not found");
+        let share = vault.heirs.iter().find(|h| h.heir == heir).expect("Not an heir");
 
-        let deadman_limit = 15_552_000; // 180 Days
         let time_elapsed = env.ledger().timestamp() - vault.last_heartbeat;
 
-        if time_elapsed < deadman_limit {
+        if time_elapsed < vault.deadman_period {
             panic!("Owner is still alive");
         }
+
+        // The first claim freezes the estate so every heir's share is computed on the same totals
+        if !vault.is_settled {
+            vault.is_settled = true;
+            vault.settled_at = env.ledger().timestamp();
+            vault.is_locked = false;
+            vault.estate = vault.assets.clone();
+        }
+        if vault.estate.is_empty() { panic!("Vault is empty"); }
+
+        let claimed_key = DataKey::HeirClaimed(target_user, heir.clone());
+        let mut claimed: Map<Address, i128> = env.storage().persistent().get(&claimed_key).unwrap_or(Map::new(&env));
+        let mut payouts: Vec<(Address, i128)> = Vec::new(&env);
+        let mut fully_claimed = true;
+        for token in vault.estate.keys().iter() {
+            let already = claimed.get(token.clone()).unwrap_or(0);
+            fully_claimed &= already >= heir_entitlement(&vault, &share, &token);
+            let amount = vested_amount(&env, &vault, &share, &token) - already;
+            if amount > 0 {
+                let held = vault.assets.get(token.clone()).unwrap_or(0);
+                vault.assets.set(token.clone(), held - amount);
+                claimed.set(token.clone(), already + amount);
+                payouts.push_back((token, amount));
+            }
+        }
+        if fully_claimed { panic!("Share already claimed"); }
+        if payouts.is_empty() { panic!("Nothing vested yet"); }
+        env.storage().persistent().set(&key, &vault);
+        env.storage().persistent().set(&claimed_key, &claimed);
+
+        for (token, amount) in payouts.iter() {
+            token::Client::new(&env, &token).transfer(&env.current_contract_address(), &heir, &amount);
+        }
+    }
+
+    pub fn get_vested_amount(env: Env, user: Address, heir: Address, token: Address) -> i128 {
+        let vault: LegacyVault = env.storage().persistent().get(&DataKey::Vault(user)).expect("Vault not found");
+        let share = vault.heirs.iter().find(|h| h.heir == heir).expect("Not an heir");
+        if !vault.is_settled { return 0; }
+        vested_amount(&env, &vault, &share, &token)
+    }
+
+    pub fn get_claimed_amount(env: Env, user: Address, heir: Address, token: Address) -> i128 {
+        let claimed: Map<Address, i128> = env.storage().persistent().get(&DataKey::HeirClaimed(user, heir)).unwrap_or(Map::new(&env));
+        claimed.get(token).unwrap_or(0)
+    }
+
+    pub fn update_heir(env: Env, user: Address, heirs: Vec<HeirShare>) {
+        user.require_auth();
+        check_heir_shares(&heirs);
+        propose_vault_change(&env, user, VaultChange::Heirs(heirs));
+    }
+
+    pub fn update_deadman_period(env: Env, user: Address, period: u64) {
+        user.require_auth();
+        if period < MIN_DEADMAN_PERIOD { panic!("Deadman period too short"); }
+        propose_vault_change(&env, user, VaultChange::DeadmanPeriod(period));
+    }
+
+    pub fn update_release_schedule(env: Env, user: Address, schedule: ReleaseSchedule) {
+        user.require_auth();
+        if schedule.tranches == 0 || schedule.tranches > MAX_RELEASE_TRANCHES { panic!("Invalid tranche count"); }
+        if schedule.tranches > 1 && schedule.interval == 0 { panic!("Invalid tranche interval"); }
+        propose_vault_change(&env, user, VaultChange::ReleaseSchedule(schedule));
+    }
+
+    pub fn confirm_vault_change(env: Env, user: Address) {
+        user.require_auth();
+        let pending_key = DataKey::PendingVaultChange(user.clone());
+        let pending: PendingVaultChange = env.storage().persistent().get(&pending_key).expect("No pending change");
+        if env.ledger().timestamp() < pending.effective_at { panic!("Change is still time-locked"); }
+
+        let key = DataKey::Vault(user.clone());
+        let mut vault: LegacyVault = env.storage().persistent().get(&key).expect("Vault not found");
+        if vault.is_settled { panic!("Vault already settled"); }
+        if vault.is_frozen { panic!("Vault is frozen"); }
+        let mut withdrawal = None;
+        match pending.change {
+            VaultChange::Heirs(heirs) => vault.heirs = heirs,
+            VaultChange::DeadmanPeriod(period) => vault.deadman_period = period,
+            VaultChange::ReleaseSchedule(schedule) => vault.release_schedule = schedule,
+            VaultChange::Withdrawal(token, amount) => {
+                // A medical release may have spent part of it during the delay
+                let held = vault.assets.get(token.clone()).unwrap_or(0);
+                if held < amount { panic!("Insufficient vault balance"); }
+                vault.assets.set(token.clone(), held - amount);
+                withdrawal = Some((token, amount));
+            }
+        }
+        env.storage().persistent().set(&key, &vault);
+        env.storage().persistent().remove(&pending_key);
+        if let Some((token, amount)) = withdrawal {
+            token::Client::new(&env, &token).transfer(&env.current_contract_address(), &user, &amount);
+        }
+    }
+
+    pub fn cancel_vault_change(env: Env, user: Address) {
+        user.require_auth();
+        let pending_key = DataKey::PendingVaultChange(user);
+        if !env.storage().persistent().has(&pending_key) { panic!("No pending change"); }
+        env.storage().persistent().remove(&pending_key);
+    }
+
+    pub fn veto_vault_change(env: Env, witness: Address, target_user: Address) {
+        witness.require_auth();
+        require_witness(&env, &witness, &target_user);
+        let pending_key = DataKey::PendingVaultChange(target_user.clone());
+        if !env.storage().persistent().has(&pending_key) { panic!("No pending change"); }
+        env.storage().persistent().remove(&pending_key);
+        VaultChangeVetoed { owner: target_user, witness }.publish(&env);
+    }
+
+    pub fn get_pending_vault_change(env: Env, user: Address) -> Option<PendingVaultChange> {
+        env.storage().persistent().get(&DataKey::PendingVaultChange(user))
+    }
+
+    pub fn get_vault_balance(env: Env, user: Address, token: Address) -> i128 {
+        let vault: LegacyVault = env.storage().persistent().get(&DataKey::Vault(user)).expect("Vault not found");
+        vault.assets.get(token).unwrap_or(0)
     }
 
     // --- FEATURE 2: SECURITY CIRCLE ---
 
-    pub fn assign_witnesses(env: Env, user: Address, witnesses: Vec<Address>) {
+    pub fn invite_witness(env: Env, user: Address, witness: Address) {
+        user.require_auth();
+        if witness == user { panic!("Cannot witness yourself"); }
+        let circle = Self::get_witnesses(env.clone(), user.clone());
+        let mut invites = Self::get_witness_invites(env.clone(), user.clone());
+        if circle.contains(witness.clone()) || invites.contains(witness.clone()) { panic!("Already invited"); }
+        let max_size = Self::get_circle_config(env.clone(), user.clone()).max_size;
+        if circle.len() + invites.len() >= max_size { panic!("Circle is full"); }
+
+        invites.push_back(witness.clone());
+        env.storage().persistent().set(&DataKey::WitnessInvites(user.clone()), &invites);
+        WitnessInvited { owner: user, witness }.publish(&env);
+    }
+
+    pub fn accept_witness(env: Env, witness: Address, user: Address) {
+        witness.require_auth();
+        let mut invites = Self::get_witness_invites(env.clone(), user.clone());
+        let index = invites.first_index_of(witness.clone()).expect("No invite");
+        invites.remove(index);
+        env.storage().persistent().set(&DataKey::WitnessInvites(user.clone()), &invites);
+
+        let mut circle = Self::get_witnesses(env.clone(), user.clone());
+        circle.push_back(witness.clone());
+        env.storage().persistent().set(&DataKey::Witnesses(user.clone()), &circle);
+
+        let mut joined = Self::get_joined_circles(env.clone(), witness.clone());
+        joined.push_back(user);
+        env.storage().persistent().set(&DataKey::JoinedCircles(witness), &joined);
+    }
+
+    /// Owner withdraws an invite at once, or schedules an accepted witness's removal
+    /// behind the same delay as vault changes. Until then the witness can still veto,
+    /// so a stolen key can't clear the circle ahead of an heir change.
+    pub fn remove_witness(env: Env, user: Address, witness: Address) {
+        user.require_auth();
+        if Self::get_witness_invites(env.clone(), user.clone()).contains(witness.clone()) {
+            exit_circle(&env, &user, &witness);
+            return;
+        }
+        require_witness(&env, &witness, &user);
+        let mut pending = Self::get_pending_removals(env.clone(), user.clone());
+        if pending.contains_key(witness.clone()) { panic!("Removal already scheduled"); }
+        let effective_at = env.ledger().timestamp() + VAULT_CHANGE_DELAY;
+        pending.set(witness.clone(), effective_at);
+        env.storage().persistent().set(&DataKey::PendingRemovals(user.clone()), &pending);
+        WitnessRemovalScheduled { owner: user, witness, effective_at }.publish(&env);
+    }
+
+    /// The owner may not shrink a ready circle below its thresholds; lower them first.
+    pub fn confirm_witness_removal(env: Env, user: Address, witness: Address) {
+        user.require_auth();
+        let effective_at = Self::get_pending_removals(env.clone(), user.clone()).get(witness.clone()).expect("No removal scheduled");
+        if env.ledger().timestamp() < effective_at { panic!("Removal delay not over"); }
+        let size = Self::get_witnesses(env.clone(), user.clone()).len();
+        let config = Self::get_circle_config(env.clone(), user.clone());
+        let needed = config.medical_threshold.max(config.panic_threshold);
+        if size >= needed && size - 1 < needed { panic!("Threshold exceeds circle size"); }
+        exit_circle(&env, &user, &witness);
+    }
+
+    pub fn get_pending_removals(env: Env, user: Address) -> Map<Address, u64> {
+        env.storage().persistent().get(&DataKey::PendingRemovals(user)).unwrap_or(Map::new(&env))
+    }
+
+    /// Witness leaves a circle or declines an invite. Always allowed; a circle left
+    /// below a threshold is "not ready" for that action until it recruits again.
+    pub fn leave_circle(env: Env, witness: Address, user: Address) {
+        witness.require_auth();
+        exit_circle(&env, &user, &witness);
+    }
+
+    pub fn get_witnesses(env: Env, user: Address) -> Vec<Address> {
+        env.storage().persistent().get(&DataKey::Witnesses(user)).unwrap_or(Vec::new(&env))
+    }
+
+    pub fn get_witness_invites(env: Env, user: Address) -> Vec<Address> {
+        env.storage().persistent().get(&DataKey::WitnessInvites(user)).unwrap_or(Vec::new(&env))
+    }
+
+    /// Every circle `witness` has accepted a seat in.
+    pub fn get_joined_circles(env: Env, witness: Address) -> Vec<Address> {
+        env.storage().persistent().get(&DataKey::JoinedCircles(witness)).unwrap_or(Vec::new(&env))
+    }
+
+    /// Thresholds are checked against `max_size`, so a circle can be configured before
+    /// anyone is invited; `require_circle_ready` holds actions until enough have accepted.
+    pub fn set_circle_config(env: Env, user: Address, max_size: u32, medical_threshold: u32, panic_threshold: u32) {
         user.require_auth();
-        if witnesses.len() > 5 { panic!("Max 5 witnesses"); }
-        env.storage().persistent().set(&DataKey::Witnesses(user), &witnesses);
+        let seats = Self::get_witnesses(env.clone(), user.clone()).len() + Self::get_witness_invites(env.clone(), user.clone()).len();
+        if max_size > MAX_CIRCLE_SIZE || max_size < seats { panic!("Invalid circle size"); }
+        if medical_threshold == 0 || panic_threshold == 0 { panic!("Threshold must be positive"); }
+        if medical_threshold > max_size || panic_threshold > max_size { panic!("Threshold exceeds circle size"); }
+        let config = CircleConfig { max_size, medical_threshold, panic_threshold };
+        env.storage().persistent().set(&DataKey::CircleConfig(user), &config);
     }
 
-    pub fn declare_emergency(env: Env, target_user: Address) {
+    /// Thresholds are never lowered to fit the circle; a circle with fewer accepted
+    /// witnesses than a threshold is "not ready" and can't take that action.
+    pub fn get_circle_config(env: Env, user: Address) -> CircleConfig {
+        env.storage().persistent().get(&DataKey::CircleConfig(user)).unwrap_or(CircleConfig {
+            max_size: DEFAULT_CIRCLE_SIZE,
+            medical_threshold: DEFAULT_CIRCLE_THRESHOLD,
+            panic_threshold: DEFAULT_CIRCLE_THRESHOLD,
+        })
+    }
+
+    pub fn declare_emergency(env: Env, caller: Address, target_user: Address, payee: Address, token: Address, amount: i128) {
+        caller.require_auth();
+        if amount <= 0 { panic!("Amount must be positive"); }
+        if caller != target_user { require_witness(&env, &caller, &target_user); }
+
         let key = DataKey::Emergency(target_user.clone());
-        if env.storage().persistent().has(&key) { panic!("Emergency active"); }
+        if let Some(mut previous) = env.storage().persistent().get::<_, MedicalEmergency>(&key) {
+            if !is_expired(&env, &previous) { panic!("Emergency active"); }
+            previous.status = EmergencyStatus::Expired;
+            archive_emergency(&env, previous);
+        }
 
+        let now = env.ledger().timestamp();
         let emergency = MedicalEmergency {
-            target_user: target_user,
-            votes_collected: 0,
-            is_unlocked: false,
+            target_user,
+            declared_by: caller,
+            voters: Vec::new(&env),
+            status: EmergencyStatus::Active,
+            declared_at: now,
+            expires_at: now + EMERGENCY_WINDOW,
+            payee,
+            token,
+            requested: amount,
+            released: 0,
         };
         env.storage().persistent().set(&key, &emergency);
     }
 
     pub fn witness_vote_medical(env: Env, witness: Address, target_user: Address) {
         witness.require_auth();
-        let circle: Vec<Address> = env.storage().persistent().get(&DataKey::Witnesses(target_user.clone())).expect("No Circle");
-        if !circle.contains(witness.clone()) { panic!("Not a witness"); }
+        require_witness(&env, &witness, &target_user);
+        let threshold = Self::get_circle_config(env.clone(), target_user.clone()).medical_threshold;
+        require_circle_ready(&env, &target_user, threshold);
 
         let key = DataKey::Emergency(target_user.clone());
         let mut emergency: MedicalEmergency = env.storage().persistent().get(&key).expect("No emergency");
-        emergency.votes_collected += 1;
-        if emergency.votes_collected >= 3 {
-            emergency.is_unlocked = true; 
+        require_open_emergency(&env, &emergency);
+        if emergency.voters.contains(witness.clone()) { panic!("Already voted"); }
+        emergency.voters.push_back(witness);
+        if emergency.voters.len() >= threshold {
+            emergency.status = EmergencyStatus::Unlocked;
+            emergency.released = release_medical_funds(&env, &emergency);
         }
         env.storage().persistent().set(&key, &emergency);
     }
 
+    pub fn withdraw_medical_vote(env: Env, witness: Address, target_user: Address) {
+        witness.require_auth();
+        let key = DataKey::Emergency(target_user);
+        let mut emergency: MedicalEmergency = env.storage().persistent().get(&key).expect("No emergency");
+        if emergency.status == EmergencyStatus::Unlocked { panic!("Threshold already reached"); }
+        require_open_emergency(&env, &emergency);
+        let index = emergency.voters.first_index_of(witness).expect("No vote to withdraw");
+        emergency.voters.remove(index);
+        env.storage().persistent().set(&key, &emergency);
+    }
+
+    pub fn cancel_emergency(env: Env, user: Address) {
+        user.require_auth();
+        let key = DataKey::Emergency(user);
+        let mut emergency: MedicalEmergency = env.storage().persistent().get(&key).expect("No emergency");
+        if emergency.status != EmergencyStatus::Active { panic!("Emergency not active"); }
+        emergency.status = EmergencyStatus::Cancelled;
+        env.storage().persistent().remove(&key);
+        archive_emergency(&env, emergency);
+    }
+
+    /// Closes an unlocked emergency once it has been dealt with, so a new one can be declared.
+    pub fn resolve_emergency(env: Env, caller: Address, target_user: Address) {
+        caller.require_auth();
+        if caller != target_user { require_witness(&env, &caller, &target_user); }
+        let key = DataKey::Emergency(target_user);
+        let mut emergency: MedicalEmergency = env.storage().persistent().get(&key).expect("No emergency");
+        if emergency.status != EmergencyStatus::Unlocked { panic!("Emergency not unlocked"); }
+        emergency.status = EmergencyStatus::Resolved;
+        env.storage().persistent().remove(&key);
+        archive_emergency(&env, emergency);
+    }
+
+    pub fn get_medical_payouts(env: Env, user: Address) -> Vec<MedicalPayout> {
+        env.storage().persistent().get(&DataKey::MedicalPayouts(user)).unwrap_or(Vec::new(&env))
+    }
+
+    pub fn get_emergency_history(env: Env, user: Address) -> Vec<MedicalEmergency> {
+        env.storage().persistent().get(&DataKey::EmergencyHistory(user)).unwrap_or(Vec::new(&env))
+    }
+
+    pub fn get_emergency(env: Env, user: Address) -> Option<MedicalEmergency> {
+        env.storage().persistent().get(&DataKey::Emergency(user))
+    }
+
     pub fn panic_button(env: Env, witness: Address, target_user: Address) {
         witness.require_auth();
-        let circle: Vec<Address> = env.storage().persistent().get(&DataKey::Witnesses(target_user.clone())).expect("No Circle");
-        if !circle.contains(witness.clone()) { panic!("Not a witness"); }
+        require_witness(&env, &witness, &target_user);
+        let threshold = Self::get_circle_config(env.clone(), target_user.clone()).panic_threshold;
+        require_circle_ready(&env, &target_user, threshold);
+        if env.storage().persistent().has(&DataKey::Panic(target_user.clone())) { panic!("Panic already active"); }
+        let history = Self::get_panic_history(env.clone(), target_user.clone());
+        if let Some(last) = history.last() {
+            if env.ledger().timestamp() < last.resolved_at + PANIC_COOLDOWN { panic!("Panic cooling down"); }
+        }
 
         let key = DataKey::PanicVotes(target_user.clone());
-        let mut votes: u32 = env.storage().persistent().get(&key).unwrap_or(0);
-        votes += 1;
-        env.storage().persistent().set(&key, &votes);
+        let mut voters: Vec<Address> = env.storage().persistent().get(&key).unwrap_or(Vec::new(&env));
+        if voters.contains(witness.clone()) { panic!("Already voted"); }
+        voters.push_back(witness);
+
+        if voters.len() < threshold {
+            env.storage().persistent().set(&key, &voters);
+            return;
+        }
+
+        let now = env.ledger().timestamp();
+        let mut vault: LegacyVault = env.storage().persistent().get(&DataKey::Vault(target_user.clone())).expect("Vault not found");
+        let event = PanicEvent {
+            triggered_at: now,
+            triggered_by: voters,
+            previous_heartbeat: vault.last_heartbeat,
+            status: PanicStatus::Active,
+            lift_voters: Vec::new(&env),
+            resolved_at: 0,
+        };
+        vault.is_frozen = true;
+        let time_warp = vault.deadman_period - PANIC_HEIR_DELAY;
+        vault.last_heartbeat = now.saturating_sub(time_warp);
+        env.storage().persistent().set(&DataKey::Vault(target_user.clone()), &vault);
+        // The votes are spent on this panic; a later one needs a fresh round
+        env.storage().persistent().remove(&key);
+        env.storage().persistent().set(&DataKey::Panic(target_user), &event);
+    }
 
-        if votes >= 3 {
-            let mut vault: LegacyVault = env.storage().persistent().get(&DataKey::Vault(target_user.clone())).expect("Vault not found");
-            vault.is_frozen = true;
-            let time_warp = 15_552_000 - 604_800; 
-            vault.last_heartbeat = env.ledger().timestamp() - time_warp;
-            env.storage().persistent().set(&DataKey::Vault(target_user), &vault);
+    /// Lets the circle undo a mistaken panic with the same threshold that triggered it.
+    pub fn lift_panic(env: Env, witness: Address, target_user: Address) {
+        witness.require_auth();
+        require_witness(&env, &witness, &target_user);
+        let threshold = Self::get_circle_config(env.clone(), target_user.clone()).panic_threshold;
+        require_circle_ready(&env, &target_user, threshold);
+        let mut event: PanicEvent = env.storage().persistent().get(&DataKey::Panic(target_user.clone())).expect("No active panic");
+        if event.lift_voters.contains(witness.clone()) { panic!("Already voted"); }
+        event.lift_voters.push_back(witness);
+
+        if event.lift_voters.len() < threshold {
+            env.storage().persistent().set(&DataKey::Panic(target_user), &event);
+            return;
         }
+
+        let vault_key = DataKey::Vault(target_user.clone());
+        let mut vault: LegacyVault = env.storage().persistent().get(&vault_key).expect("Vault not found");
+        vault.is_frozen = false;
+        vault.last_heartbeat = event.previous_heartbeat;
+        env.storage().persistent().set(&vault_key, &vault);
+        close_panic(&env, &target_user, event, PanicStatus::LiftedByCircle);
+    }
+
+    pub fn get_panic(env: Env, user: Address) -> Option<PanicEvent> {
+        env.storage().persistent().get(&DataKey::Panic(user))
+    }
+
+    pub fn get_panic_history(env: Env, user: Address) -> Vec<PanicEvent> {
+        env.storage().persistent().get(&DataKey::PanicHistory(user)).unwrap_or(Vec::new(&env))
+    }
+
+    pub fn withdraw_panic_vote(env: Env, witness: Address, target_user: Address) {
+        witness.require_auth();
+        let key = DataKey::PanicVotes(target_user.clone());
+        let mut voters: Vec<Address> = env.storage().persistent().get(&key).unwrap_or(Vec::new(&env));
+        if voters.len() >= Self::get_circle_config(env.clone(), target_user).panic_threshold { panic!("Threshold already reached"); }
+        let index = voters.first_index_of(witness).expect("No vote to withdraw");
+        voters.remove(index);
+        env.storage().persistent().set(&key, &voters);
+    }
+
+    pub fn get_panic_votes(env: Env, user: Address) -> Vec<Address> {
+        env.storage().persistent().get(&DataKey::PanicVotes(user)).unwrap_or(Vec::new(&env))
     }
 
     // --- FEATURE 3: MERCHANT TRUST ---
 
-    pub fn stake(env: Env, user: Address) {
+    pub fn init(env: Env, admin: Address) {
+        if env.storage().instance().has(&DataKey::Admin) { panic!("Already initialized"); }
+        env.storage().instance().set(&DataKey::Admin, &admin);
+    }
+
+    pub fn get_admin(env: Env) -> Address {
+        env.storage().instance().get(&DataKey::Admin).expect("Not initialized")
+    }
+
+    pub fn set_bond_config(env: Env, token: Address, amount: i128, lock_period: u64) {
+        require_admin(&env);
+        if amount <= 0 { panic!("Amount must be positive"); }
+        env.storage().instance().set(&DataKey::BondConfig, &BondConfig { token, amount, lock_period });
+    }
+
+    pub fn get_bond_config(env: Env) -> Option<BondConfig> {
+        env.storage().instance().get(&DataKey::BondConfig)
+    }
+
+    /// `referrer` is only recorded on the first stake; its reward waits for the Poverty Observation window.
+    pub fn stake(env: Env, user: Address, referrer: Option<Address>) {
         user.require_auth();
-        let mut merchant = env.storage().persistent().get(&DataKey::Merchant(user.clone())).unwrap_or(Merchant {
-            trust_score: 0, bond_staked: false, bzr_balance: 0, badges: Vec::new(&env), 
-            is_disputed: false, nickname: Symbol::new(&env, "User"), messages: Vec::new(&env)
-        });
+        if referrer.as_ref() == Some(&user) { panic!("Cannot refer yourself"); }
+        let mut merchant = env.storage().persistent().get(&DataKey::Merchant(user.clone())).unwrap_or(new_merchant(&env, "User"));
+        if merchant.status == MerchantStatus::Exiled { panic!("Merchant is exiled"); }
         if merchant.bond_staked { panic!("Already bonded"); }
+
+        let config = Self::get_bond_config(env.clone()).expect("Bond not configured");
+        token::Client::new(&env, &config.token).transfer(&user, env.current_contract_address(), &config.amount);
         merchant.bond_staked = true;
-        merchant.trust_score += 10;
-        env.storage().persistent().set(&DataKey::Merchant(user), &merchant);
+        merchant.bond_token = Some(config.token);
+        merchant.bond_amount = config.amount;
+        merchant.bond_unlocks_at = env.ledger().timestamp() + config.lock_period;
+        adjust_trust(&env, &user, &mut merchant, STAKE_BONUS);
+        env.storage().persistent().set(&DataKey::Merchant(user.clone()), &merchant);
+
+        // Only the first stake starts the window; unbonding and re-staking never resets it
+        let observation_key = DataKey::Observation(user.clone());
+        if !env.storage().persistent().has(&observation_key) {
+            let mut observation = PovertyObservation { start_time: 0, is_active: false };
+            observation.start_observation(env.ledger().timestamp());
+            env.storage().persistent().set(&observation_key, &observation);
+
+            if let Some(referrer) = referrer {
+                if !env.storage().persistent().has(&DataKey::Merchant(referrer.clone())) { panic!("Referrer not found"); }
+                env.storage().persistent().set(&DataKey::Referrer(user.clone()), &referrer);
+                let mut referrals = Self::get_referrals(env.clone(), referrer.clone());
+                referrals.push_back(user);
+                env.storage().persistent().set(&DataKey::Referrals(referrer), &referrals);
+            }
+        }
+    }
+
+    /// Pays the referrer once the referee has completed their Poverty Observation window.
+    /// Anyone may trigger it; each referrer is paid for at most MAX_REFERRALS_PER_EPOCH referees per epoch.
+    pub fn claim_referral_reward(env: Env, referee: Address) -> i128 {
+        let referrer = Self::get_referrer(env.clone(), referee.clone()).expect("No referrer");
+        let paid_key = DataKey::ReferralPaid(referee.clone());
+        if env.storage().persistent().has(&paid_key) { panic!("Referral already paid"); }
+        if Self::is_exiled(env.clone(), referee.clone()) || Self::is_exiled(env.clone(), referrer.clone()) { panic!("Merchant is exiled"); }
+        if !Self::verify_status(env.clone(), referee) { panic!("Observation window not complete"); }
+
+        let count_key = DataKey::ReferralRewards(referrer.clone(), emission_epoch(&env));
+        let count: u32 = env.storage().persistent().get(&count_key).unwrap_or(0);
+        if count >= MAX_REFERRALS_PER_EPOCH { panic!("Referral cap reached"); }
+        // A short mint (no token yet, or the supply or epoch budget is spent) reverts so the claim can be retried
+        let minted = mint_bzr(&env, &referrer, REFERRAL_REWARD, MintReason::Referral);
+        if minted < REFERRAL_REWARD { panic!("Referral reward unavailable"); }
+        env.storage().persistent().set(&count_key, &(count + 1));
+        env.storage().persistent().set(&paid_key, &true);
+        minted
+    }
+
+    pub fn get_referrer(env: Env, user: Address) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::Referrer(user))
+    }
+
+    /// Direct referees only; walk the results to expand the whole tree.
+    pub fn get_referrals(env: Env, referrer: Address) -> Vec<Address> {
+        env.storage().persistent().get(&DataKey::Referrals(referrer)).unwrap_or(Vec::new(&env))
+    }
+
+    /// Returns the escrowed bond once the lock period has passed and unbonds the merchant.
+    pub fn withdraw(env: Env, user: Address) {
+        user.require_auth();
+        let key = DataKey::Merchant(user.clone());
+        let mut merchant: Merchant = env.storage().persistent().get(&key).expect("Merchant not found");
+        if !merchant.bond_staked { panic!("Not bonded"); }
+        if env.ledger().timestamp() < merchant.bond_unlocks_at { panic!("Bond is still locked"); }
+
+        let refund = merchant.bond_token.clone().map(|token| (token, merchant.bond_amount));
+        merchant.bond_staked = false;
+        merchant.bond_token = None;
+        merchant.bond_amount = 0;
+        merchant.bond_unlocks_at = 0;
+        adjust_trust(&env, &user, &mut merchant, -STAKE_BONUS);
+        env.storage().persistent().set(&key, &merchant);
+
+        if let Some((token, amount)) = refund {
+            if amount > 0 {
+                token::Client::new(&env, &token).transfer(&env.current_contract_address(), &user, &amount);
+            }
+        }
+    }
+
+    /// Whether the merchant has completed their Poverty Observation window.
+    pub fn verify_status(env: Env, user: Address) -> bool {
+        let key = DataKey::Observation(user);
+        let Some(mut observation) = env.storage().persistent().get::<_, PovertyObservation>(&key) else { return false; };
+        let was_active = observation.is_active;
+        let verified = observation.verify_window(env.ledger().timestamp());
+        if was_active && verified {
+            env.storage().persistent().set(&key, &observation);
+        }
+        verified
+    }
+
+    pub fn is_bonded(env: Env, user: Address) -> bool {
+        env.storage().persistent().get::<_, Merchant>(&DataKey::Merchant(user)).is_some_and(|m| m.bond_staked)
     }
 
     pub fn vouch(env: Env, voucher: Address, target: Address) {
         voucher.require_auth();
-        // SAFE INITIALIZATION: No more "Target not found" traps
-        let mut target_data = env.storage().persistent().get(&DataKey::Merchant(target.clone())).unwrap_or(Merchant {
-            trust_score: 0, bond_staked: false, bzr_balance: 0, badges: Vec::new(&env), 
-            is_disputed: false, nickname: Symbol::new(&env, "NewUser"), messages: Vec::new(&env)
-        });
+        if voucher == target { panic!("Cannot vouch for yourself"); }
+        let mut voucher_data: Merchant = env.storage().persistent().get(&DataKey::Merchant(voucher.clone())).expect("Voucher not found");
+        if voucher_data.status == MerchantStatus::Exiled { panic!("Merchant is exiled"); }
+        if !voucher_data.bond_staked { panic!("Voucher is not bonded"); }
+        if voucher_data.is_disputed { panic!("Voucher is disputed"); }
+
+        let vouch_key = DataKey::Vouch(voucher.clone(), target.clone());
+        if env.storage().persistent().has(&vouch_key) { panic!("Already vouched"); }
+        if env.storage().persistent().has(&DataKey::RevokedVouch(voucher.clone(), target.clone())) { panic!("Vouch was revoked"); }
+        if env.storage().persistent().has(&DataKey::PendingSlash(target.clone())) { panic!("Slash pending"); }
+        if Self::get_vouchers(env.clone(), target.clone()).len() >= MAX_VOUCHERS { panic!("Target has too many vouchers"); }
+
+        // Only bonded merchants can be vouched for, so rewards can't be farmed off fresh addresses
+        let mut target_data: Merchant = env.storage().persistent().get(&DataKey::Merchant(target.clone())).expect("Target not found");
+        if target_data.status == MerchantStatus::Exiled { panic!("Target is exiled"); }
+        if !target_data.bond_staked { panic!("Target is not bonded"); }
+
+        // Vouching counts as activity, and a stale voucher's weight comes from their decayed score
+        apply_decay(&env, &voucher, &mut voucher_data);
+        voucher_data.last_activity = env.ledger().timestamp();
+        env.storage().persistent().set(&DataKey::Merchant(voucher.clone()), &voucher_data);
+
+        let weight = vouch_weight(voucher_data.trust_score);
+        adjust_trust(&env, &target, &mut target_data, weight);
+        env.storage().persistent().set(&DataKey::Merchant(target.clone()), &target_data);
+
+        let record = VouchRecord { voucher: voucher.clone(), target: target.clone(), weight, timestamp: env.ledger().timestamp() };
+        env.storage().persistent().set(&vouch_key, &record);
+        let mut vouchers = Self::get_vouchers(env.clone(), target.clone());
+        vouchers.push_back(voucher.clone());
+        env.storage().persistent().set(&DataKey::Vouchers(target.clone()), &vouchers);
+        mint_bzr(&env, &voucher, VOUCH_REWARD, MintReason::Vouch);
+        Vouched { voucher, target, weight }.publish(&env);
+    }
+
+    /// Withdraws a vouch and takes back exactly the weight it added.
+    pub fn revoke_vouch(env: Env, voucher: Address, target: Address) {
+        voucher.require_auth();
+        let vouch_key = DataKey::Vouch(voucher.clone(), target.clone());
+        let record: VouchRecord = env.storage().persistent().get(&vouch_key).expect("No vouch");
+        let key = DataKey::Merchant(target.clone());
+        let mut target_data: Merchant = env.storage().persistent().get(&key).expect("Merchant not found");
+        // Vouchers can't slip out ahead of an open dispute or while their slash is still being applied
+        if target_data.is_disputed { panic!("Target is disputed"); }
+        if target_data.status == MerchantStatus::Exiled { panic!("Target is exiled"); }
+        if env.storage().persistent().has(&DataKey::PendingSlash(target.clone())) { panic!("Slash pending"); }
+        env.storage().persistent().remove(&vouch_key);
+        // Without the tombstone, vouch -> revoke loops would mint VOUCH_REWARD every round
+        env.storage().persistent().set(&DataKey::RevokedVouch(voucher.clone(), target.clone()), &true);
+        let mut vouchers = Self::get_vouchers(env.clone(), target.clone());
+        if let Some(index) = vouchers.first_index_of(voucher.clone()) { vouchers.remove(index); }
+        env.storage().persistent().set(&DataKey::Vouchers(target.clone()), &vouchers);
+
+        adjust_trust(&env, &target, &mut target_data, -record.weight);
+        env.storage().persistent().set(&key, &target_data);
+        VouchRevoked { voucher, target, weight: record.weight }.publish(&env);
+    }
+
+    pub fn get_vouch(env: Env, voucher: Address, target: Address) -> Option<VouchRecord> {
+        env.storage().persistent().get(&DataKey::Vouch(voucher, target))
+    }
+
+    pub fn get_vouchers(env: Env, target: Address) -> Vec<Address> {
+        env.storage().persistent().get(&DataKey::Vouchers(target)).unwrap_or(Vec::new(&env))
+    }
+
+    /// Applies the next batch of a slash that didn't fit in one transaction. Anyone may call it.
+    pub fn continue_slash(env: Env, target: Address) {
+        if !env.storage().persistent().has(&DataKey::PendingSlash(target.clone())) { panic!("No slash pending"); }
+        slash_batch(&env, &target);
+    }
+
+    pub fn is_slash_pending(env: Env, target: Address) -> bool {
+        env.storage().persistent().has(&DataKey::PendingSlash(target))
+    }
+
+    /// Read-only: includes decay accrued since the last activity without persisting it,
+    /// blended with the oracle's graph score once one has been committed.
+    pub fn get_trust(env: Env, user: Address) -> i32 {
+        let mut merchant = env.storage().persistent().get(&DataKey::Merchant(user.clone())).unwrap_or(new_merchant(&env, "User"));
+        decay_score(&env, &mut merchant);
+        let (Some(config), Some(graph)) = (Self::get_oracle_config(env.clone()), Self::get_graph_score(env.clone(), user)) else {
+            return merchant.trust_score;
+        };
+        let w = config.blend_bps as i64;
+        let blended = (merchant.trust_score as i64 * (BASIS_POINTS as i64 - w) + graph.score as i64 * w) / BASIS_POINTS as i64;
+        blended as i32
+    }
+
+    /// Admin sets the inactivity half-life in seconds; 0 turns lazy decay off.
+    pub fn set_decay_half_life(env: Env, half_life: u64) {
+        require_admin(&env);
+        env.storage().instance().set(&DataKey::DecayHalfLife, &half_life);
+    }
+
+    pub fn get_decay_half_life(env: Env) -> u64 {
+        env.storage().instance().get(&DataKey::DecayHalfLife).unwrap_or(DEFAULT_DECAY_HALF_LIFE)
+    }
+
+    /// Admin penalty for bad actors, logged to the merchant's decay history.
+    pub fn decay(env: Env, target: Address) {
+        let admin = require_admin(&env);
+        let key = DataKey::Merchant(target.clone());
+        let mut data: Merchant = env.storage().persistent().get(&key).expect("Merchant not found");
+        adjust_trust(&env, &target, &mut data, -DECAY_PENALTY);
+        env.storage().persistent().set(&key, &data);
+
+        let mut history = Self::get_decay_history(env.clone(), target.clone());
+        if history.len() >= MAX_DECAY_HISTORY { history.pop_front(); }
+        history.push_back(DecayRecord { admin: admin.clone(), penalty: DECAY_PENALTY, score_after: data.trust_score, timestamp: env.ledger().timestamp() });
+        env.storage().persistent().set(&DataKey::DecayHistory(target.clone()), &history);
+        TrustDecayed { merchant: target, admin, penalty: DECAY_PENALTY }.publish(&env);
+    }
+
+    pub fn get_decay_history(env: Env, user: Address) -> Vec<DecayRecord> {
+        env.storage().persistent().get(&DataKey::DecayHistory(user)).unwrap_or(Vec::new(&env))
+    }
+
+    /// Read-only, like `get_trust`: a Seal that lazy decay has taken below the band is not reported.
+    pub fn has_seal(env: Env, user: Address) -> bool {
+        let Some(mut merchant) = env.storage().persistent().get::<_, Merchant>(&DataKey::Merchant(user)) else { return false; };
+        decay_seal(&env, &mut merchant);
+        merchant.badges.contains(seal_badge())
+    }
+
+    pub fn raise_dispute(env: Env, accuser: Address, target: Address) {
+        accuser.require_auth();
+        if accuser == target { panic!("Cannot dispute yourself"); }
+        let key = DataKey::Merchant(target);
+        let mut data: Merchant = env.storage().persistent().get(&key).expect("Merchant not found");
+        if data.is_disputed { panic!("Dispute already open"); }
+        data.is_disputed = true;
+        env.storage().persistent().set(&key, &data);
+    }
+
+    /// Admin dismisses the dispute: the merchant is cleared with no penalty.
+    pub fn resolve_dispute(env: Env, target: Address) {
+        require_admin(&env);
+        let key = DataKey::Merchant(target);
+        let mut data: Merchant = env.storage().persistent().get(&key).expect("Merchant not found");
+        if !data.is_disputed { panic!("No open dispute"); }
+        data.is_disputed = false;
+        env.storage().persistent().set(&key, &data);
+    }
+
+    /// Admin upholds the dispute: the merchant loses trust and their vouchers are slashed.
+    pub fn uphold_dispute(env: Env, target: Address) {
+        require_admin(&env);
+        let key = DataKey::Merchant(target.clone());
+        let mut data: Merchant = env.storage().persistent().get(&key).expect("Merchant not found");
+        if !data.is_disputed { panic!("No open dispute"); }
+        data.is_disputed = false;
+        // shift_trust never slashes, so vouchers are slashed exactly once even if this exiles
+        shift_trust(&env, &target, &mut data, -DISPUTE_PENALTY);
+        slash_vouchers(&env, &target);
+        env.storage().persistent().set(&key, &data);
+    }
+
+    pub fn is_disputed(env: Env, user: Address) -> bool {
+        env.storage().persistent().get::<_, Merchant>(&DataKey::Merchant(user)).is_some_and(|m| m.is_disputed)
+    }
+
+    // --- FEATURE 4: EXILE PROTOCOL ---
+
+    pub fn set_exile_config(env: Env, trust_floor: i32, bond_floor: i128) {
+        require_admin(&env);
+        if !(TRUST_FLOOR..=TRUST_CEILING).contains(&trust_floor) { panic!("Floor out of range"); }
+        if bond_floor < 0 { panic!("Bond floor must not be negative"); }
+        if bond_floor > 0 && Self::get_bond_config(env.clone()).is_none() { panic!("Bond not configured"); }
+        env.storage().instance().set(&DataKey::ExileConfig, &ExileConfig { trust_floor, bond_floor });
+    }
+
+    pub fn get_exile_config(env: Env) -> ExileConfig {
+        env.storage().instance().get(&DataKey::ExileConfig).unwrap_or(ExileConfig {
+            trust_floor: DEFAULT_EXILE_TRUST_FLOOR,
+            bond_floor: 0,
+        })
+    }
+
+    /// Appeals are decided by the admin, which may be a DAO contract.
+    pub fn reinstate(env: Env, merchant: Address) {
+        let admin = require_admin(&env);
+        let key = DataKey::Merchant(merchant.clone());
+        let mut data: Merchant = env.storage().persistent().get(&key).expect("Merchant not found");
+        if data.status != MerchantStatus::Exiled { panic!("Merchant is not exiled"); }
+        data.status = MerchantStatus::Active;
+        // Lift the score back to the floor so the next adjustment doesn't exile again
+        let floor = Self::get_exile_config(env.clone()).trust_floor;
+        let lift = floor.saturating_sub(data.trust_score).max(0);
+        shift_trust(&env, &merchant, &mut data, lift);
+        if data.status == MerchantStatus::Exiled { panic!("Bond is below the exile floor"); }
+        env.storage().persistent().set(&key, &data);
+        MerchantReinstated { merchant, admin }.publish(&env);
+    }
+
+    pub fn is_exiled(env: Env, user: Address) -> bool {
+        env.storage().persistent().get::<_, Merchant>(&DataKey::Merchant(user)).is_some_and(|m| m.status == MerchantStatus::Exiled)
+    }
+
+    // --- FEATURE 5: MESSAGING ---
+
+    pub fn send_message(env: Env, sender: Address, recipient: Address, text: String) {
+        sender.require_auth();
+        require_not_exiled(&env, &sender);
+        let key = DataKey::Merchant(recipient);
+        let mut inbox: Merchant = env.storage().persistent().get(&key).expect("Recipient not found");
+        if inbox.messages.len() >= MAX_INBOX { inbox.messages.pop_front(); }
+        inbox.messages.push_back(Message { sender, text, timestamp: env.ledger().timestamp() });
+        env.storage().persistent().set(&key, &inbox);
+    }
+
+    pub fn get_messages(env: Env, user: Address) -> Vec<Message> {
+        env.storage().persistent().get::<_, Merchant>(&DataKey::Merchant(user)).map(|m| m.messages).unwrap_or(Vec::new(&env))
+    }
+
+    // --- FEATURE 6: TRUST GRAPH ORACLE ---
+
+    pub fn set_oracle(env: Env, oracle: Address, blend_bps: u32) {
+        require_admin(&env);
+        if blend_bps > BASIS_POINTS { panic!("Blend exceeds 100%"); }
+        env.storage().instance().set(&DataKey::OracleConfig, &OracleConfig { oracle, blend_bps });
+    }
+
+    pub fn get_oracle_config(env: Env) -> Option<OracleConfig> {
+        env.storage().instance().get(&DataKey::OracleConfig)
+    }
+
+    /// Periodic commitment of propagated scores, authorized by the oracle's signature.
+    /// Epochs must strictly increase so an old batch can't be replayed.
+    pub fn commit_graph_scores(env: Env, epoch: u64, scores: Map<Address, i32>) {
+        let config = Self::get_oracle_config(env.clone()).expect("Oracle not set");
+        config.oracle.require_auth();
+        let last: Option<u64> = env.storage().instance().get(&DataKey::GraphEpoch);
+        if last.is_some_and(|last| epoch <= last) { panic!("Stale epoch"); }
+
+        for (merchant, score) in scores.iter() {
+            let score = score.clamp(TRUST_FLOOR, TRUST_CEILING);
+            env.storage().persistent().set(&DataKey::GraphScore(merchant), &GraphScore { score, epoch });
+        }
+        env.storage().instance().set(&DataKey::GraphEpoch, &epoch);
+        GraphScoresCommitted { epoch, count: scores.len() }.publish(&env);
+    }
+
+    /// Only scores from the latest commitment count; a merchant the oracle dropped
+    /// from a later epoch falls back to their local score.
+    pub fn get_graph_score(env: Env, user: Address) -> Option<GraphScore> {
+        let latest: u64 = env.storage().instance().get(&DataKey::GraphEpoch)?;
+        let score: GraphScore = env.storage().persistent().get(&DataKey::GraphScore(user))?;
+        (score.epoch == latest).then_some(score)
+    }
+
+    // --- FEATURE 7: BZR TOKEN ---
+
+    /// Points the contract at the BZR token; this contract must be the token's admin.
+    /// Until it is set, rewards are simply not minted.
+    pub fn set_bzr_token(env: Env, token: Address) {
+        require_admin(&env);
+        env.storage().instance().set(&DataKey::BzrToken, &token);
+        // The emission schedule starts the first time a token is wired up
+        if !env.storage().instance().has(&DataKey::EmissionStart) {
+            env.storage().instance().set(&DataKey::EmissionStart, &env.ledger().timestamp());
+        }
+    }
+
+    pub fn get_bzr_token(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::BzrToken)
+    }
+
+    pub fn get_balance(env: Env, user: Address) -> i128 {
+        match Self::get_bzr_token(env.clone()) {
+            Some(bzr) => token::Client::new(&env, &bzr).balance(&user),
+            None => 0,
+        }
+    }
+
+    pub fn get_supply_stats(env: Env) -> SupplyStats {
+        let epoch = emission_epoch(&env);
+        let mut minted_by_reason = Map::new(&env);
+        for reason in [MintReason::Vouch, MintReason::Referral, MintReason::Lottery] {
+            minted_by_reason.set(reason, env.storage().persistent().get(&DataKey::MintedFor(reason)).unwrap_or(0));
+        }
+        SupplyStats {
+            cap: MAX_BZR_SUPPLY,
+            total_minted: env.storage().instance().get(&DataKey::TotalMinted).unwrap_or(0),
+            epoch,
+            epoch_budget: epoch_budget(epoch),
+            epoch_minted: env.storage().persistent().get(&DataKey::EpochMinted(epoch)).unwrap_or(0),
+            minted_by_reason,
+        }
+    }
+
+    /// Convenience wrapper; wallets can call the token's `transfer` directly.
+    pub fn transfer_bzr(env: Env, from: Address, to: Address, amount: i128) {
+        from.require_auth();
+        bzr_client(&env).transfer(&from, &to, &amount);
+    }
+
+    // --- FEATURE 8: BADGE SHOP ---
+
+    pub fn set_treasury(env: Env, treasury: Address) {
+        require_admin(&env);
+        env.storage().instance().set(&DataKey::Treasury, &treasury);
+    }
+
+    pub fn get_treasury(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Treasury)
+    }
 
-        if target_data.trust_score < 100 { target_data.trust_score += 1; }
-        env.storage().persistent().set(&DataKey::Merchant(target), &target_data);
+    /// Adds or replaces a catalog entry. The Seal is earned, never sold.
+    pub fn list_badge(env: Env, badge: Symbol, listing: BadgeListing) {
+        require_admin(&env);
+        if badge == seal_badge() { panic!("Badge is reserved"); }
+        if listing.price <= 0 { panic!("Price must be positive"); }
+        if listing.duration == Some(0) { panic!("Duration must be positive"); }
+        if !listing.burn && Self::get_treasury(env.clone()).is_none() { panic!("Treasury not set"); }
+        env.storage().persistent().set(&DataKey::Badge(badge.clone()), &listing);
+        let mut catalog = Self::get_badge_catalog(env.clone());
+        if !catalog.contains(&badge) {
+            catalog.push_back(badge);
+            env.storage().persistent().set(&DataKey::BadgeCatalog, &catalog);
+        }
+    }
+
+    /// Stops sales; badges already bought keep their expiry.
+    pub fn unlist_badge(env: Env, badge: Symbol) {
+        require_admin(&env);
+        env.storage().persistent().remove(&DataKey::Badge(badge.clone()));
+        let mut catalog = Self::get_badge_catalog(env.clone());
+        if let Some(index) = catalog.first_index_of(&badge) { catalog.remove(index); }
+        env.storage().persistent().set(&DataKey::BadgeCatalog, &catalog);
+    }
+
+    pub fn get_badge_catalog(env: Env) -> Vec<Symbol> {
+        env.storage().persistent().get(&DataKey::BadgeCatalog).unwrap_or(Vec::new(&env))
     }
 
-    pub fn get_trust(env: Env, user: Address) -> u32 {
-        let merchant = env.storage().persistent().get(&DataKey::Merchant(user)).unwrap_or(Merchant {
-            trust_score: 0, bond_staked: false, bzr_balance: 0, badges: Vec::new(&env), 
-            is_disputed: false, nickname: Symbol::new(&env, "User"), messages: Vec::new(&env)
-        });
-        merchant.trust_score
+    pub fn get_badge_listing(env: Env, badge: Symbol) -> Option<BadgeListing> {
+        env.storage().persistent().get(&DataKey::Badge(badge))
     }
-}
\ No newline at end of file
+
+    pub fn buy_badge(env: Env, user: Address, badge: Symbol) {
+        user.require_auth();
+        let key = DataKey::Merchant(user.clone());
+        let mut merchant: Merchant = env.storage().persistent().get(&key).expect("Merchant not found");
+        if merchant.status == MerchantStatus::Exiled { panic!("Merchant is exiled"); }
+        let mut listing = Self::get_badge_listing(env.clone(), badge.clone()).expect("Badge not listed");
+        prune_badges(&env, &user, &mut merchant);
+        if merchant.badges.contains(&badge) { panic!("Badge already owned"); }
+        if listing.stock == Some(0) { panic!("Sold out"); }
+        if listing.min_trust.is_some_and(|min| Self::get_trust(env.clone(), user.clone()) < min) { panic!("Trust too low"); }
+
+        let bzr = bzr_client(&env);
+        if listing.burn {
+            bzr.burn(&user, &listing.price);
+        } else {
+            bzr.transfer(&user, Self::get_treasury(env.clone()).expect("Treasury not set"), &listing.price);
+        }
+        if let Some(stock) = listing.stock {
+            listing.stock = Some(stock - 1);
+            env.storage().persistent().set(&DataKey::Badge(badge.clone()), &listing);
+        }
+
+        let expires_at = listing.duration.map_or(0, |d| env.ledger().timestamp() + d);
+        let expiry_key = DataKey::BadgeExpiry(user.clone(), badge.clone());
+        if expires_at > 0 {
+            env.storage().persistent().set(&expiry_key, &expires_at);
+        } else {
+            env.storage().persistent().remove(&expiry_key);
+        }
+        merchant.badges.push_back(badge.clone());
+        env.storage().persistent().set(&key, &merchant);
+        BadgePurchased { buyer: user, badge, price: listing.price, burned: listing.burn, expires_at }.publish(&env);
+    }
+
+    pub fn has_badge(env: Env, user: Address, badge: Symbol) -> bool {
+        Self::get_badges(env, user).contains(&badge)
+    }
+
+    /// The merchant's badges with expired purchases filtered out.
+    pub fn get_badges(env: Env, user: Address) -> Vec<Symbol> {
+        let Some(mut merchant) = env.storage().persistent().get::<_, Merchant>(&DataKey::Merchant(user.clone())) else {
+            return Vec::new(&env);
+        };
+        prune_badges(&env, &user, &mut merchant);
+        decay_seal(&env, &mut merchant);
+        merchant.badges
+    }
+
+    // --- FEATURE 9: CROWDFUNDING ---
+
+    pub fn create_campaign(env: Env, beneficiary: Address, goal: i128, deadline: u64) -> u32 {
+        beneficiary.require_auth();
+        if !env.storage().persistent().has(&DataKey::Merchant(beneficiary.clone())) { panic!("Merchant not found"); }
+        require_not_exiled(&env, &beneficiary);
+        if goal <= 0 { panic!("Goal must be positive"); }
+        if deadline <= env.ledger().timestamp() { panic!("Deadline must be in the future"); }
+
+        let id: u32 = env.storage().instance().get(&DataKey::CampaignCount).unwrap_or(0) + 1;
+        env.storage().instance().set(&DataKey::CampaignCount, &id);
+        let campaign = Campaign { beneficiary: beneficiary.clone(), goal, deadline, raised: 0, claimed: false };
+        env.storage().persistent().set(&DataKey::Campaign(id), &campaign);
+        CampaignCreated { id, beneficiary, goal, deadline }.publish(&env);
+        id
+    }
+
+    pub fn contribute(env: Env, contributor: Address, id: u32, amount: i128) {
+        contributor.require_auth();
+        if amount <= 0 { panic!("Amount must be positive"); }
+        let mut campaign = Self::get_campaign(env.clone(), id);
+        if campaign.claimed || env.ledger().timestamp() >= campaign.deadline { panic!("Campaign is closed"); }
+
+        bzr_client(&env).transfer(&contributor, env.current_contract_address(), &amount);
+        campaign.raised += amount;
+        env.storage().persistent().set(&DataKey::Campaign(id), &campaign);
+
+        let key = DataKey::Contribution(id, contributor.clone());
+        let previous: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage().persistent().set(&key, &(previous + amount));
+        let mut contributors = Self::get_contributors(env.clone(), id);
+        if !contributors.contains(&contributor) {
+            contributors.push_back(contributor);
+            env.storage().persistent().set(&DataKey::Contributors(id), &contributors);
+        }
+    }
+
+    /// The beneficiary may claim as soon as the goal is met, even before the deadline.
+    pub fn claim_campaign(env: Env, id: u32) {
+        let mut campaign = Self::get_campaign(env.clone(), id);
+        campaign.beneficiary.require_auth();
+        if campaign.claimed { panic!("Already claimed"); }
+        if campaign.raised < campaign.goal { panic!("Goal not met"); }
+        campaign.claimed = true;
+        env.storage().persistent().set(&DataKey::Campaign(id), &campaign);
+        bzr_client(&env).transfer(&env.current_contract_address(), &campaign.beneficiary, &campaign.raised);
+    }
+
+    pub fn refund(env: Env, contributor: Address, id: u32) {
+        contributor.require_auth();
+        let mut campaign = Self::get_campaign(env.clone(), id);
+        if env.ledger().timestamp() < campaign.deadline { panic!("Campaign still running"); }
+        if campaign.claimed || campaign.raised >= campaign.goal { panic!("Goal was met"); }
+
+        let key = DataKey::Contribution(id, contributor.clone());
+        let amount: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        if amount == 0 { panic!("Nothing to refund"); }
+        env.storage().persistent().set(&key, &0i128);
+        // `raised` only shrinks past the deadline, so it can't dip a met goal back below it
+        campaign.raised -= amount;
+        env.storage().persistent().set(&DataKey::Campaign(id), &campaign);
+        bzr_client(&env).transfer(&env.current_contract_address(), &contributor, &amount);
+    }
+
+    pub fn get_campaign(env: Env, id: u32) -> Campaign {
+        env.storage().persistent().get(&DataKey::Campaign(id)).expect("Campaign not found")
+    }
+
+    pub fn get_contributors(env: Env, id: u32) -> Vec<Address> {
+        env.storage().persistent().get(&DataKey::Contributors(id)).unwrap_or(Vec::new(&env))
+    }
+
+    pub fn get_contribution(env: Env, id: u32, contributor: Address) -> i128 {
+        env.storage().persistent().get(&DataKey::Contribution(id, contributor)).unwrap_or(0)
+    }
+}
+
+// ============================================================
+// 🔧 HELPERS
+// ============================================================
+
+fn new_merchant(env: &Env, nickname: &str) -> Merchant {
+    Merchant {
+        trust_score: 0, bond_staked: false, bond_token: None, bond_amount: 0, bond_unlocks_at: 0,
+        badges: Vec::new(env), is_disputed: false,
+        nickname: Symbol::new(env, nickname), messages: Vec::new(env),
+        status: MerchantStatus::Active, last_activity: env.ledger().timestamp(),
+    }
+}
+
+/// The single entry point for every Trust Variance change, positive or negative.
+fn adjust_trust(env: &Env, user: &Address, merchant: &mut Merchant, delta: i32) {
+    if shift_trust(env, user, merchant, delta) {
+        slash_vouchers(env, user);
+    }
+}
+
+/// Applies `delta` with the seal and exile checks but without slashing anyone;
+/// returns true if this change exiled the merchant.
+fn shift_trust(env: &Env, user: &Address, merchant: &mut Merchant, delta: i32) -> bool {
+    decay_score(env, merchant);
+    merchant.trust_score = merchant.trust_score.saturating_add(delta).clamp(TRUST_FLOOR, TRUST_CEILING);
+    // Only gains count as activity; penalties must not reset the decay clock
+    if delta > 0 { merchant.last_activity = env.ledger().timestamp(); }
+    update_seal(env, user, merchant);
+    check_exile(env, user, merchant)
+}
+
+/// Penalizes everyone who vouched for `target` in proportion to their vouch weight.
+/// Penalties don't cascade: a voucher exiled by a slash doesn't slash their own vouchers.
+/// The first SLASH_BATCH vouchers are slashed now and the rest through `continue_slash`;
+/// a trigger arriving while a slash is still pending is folded into it.
+fn slash_vouchers(env: &Env, target: &Address) {
+    let key = DataKey::PendingSlash(target.clone());
+    if env.storage().persistent().has(&key) { return; }
+    env.storage().persistent().set(&key, &0u32);
+    slash_batch(env, target);
+}
+
+fn slash_batch(env: &Env, target: &Address) {
+    let key = DataKey::PendingSlash(target.clone());
+    let start: u32 = env.storage().persistent().get(&key).unwrap_or(0);
+    let vouchers: Vec<Address> = env.storage().persistent().get(&DataKey::Vouchers(target.clone())).unwrap_or(Vec::new(env));
+    let end = (start + SLASH_BATCH).min(vouchers.len());
+    for voucher in vouchers.slice(start..end).iter() {
+        let record: VouchRecord = env.storage().persistent().get(&DataKey::Vouch(voucher.clone(), target.clone())).expect("No vouch");
+        let merchant_key = DataKey::Merchant(voucher.clone());
+        let Some(mut data) = env.storage().persistent().get::<_, Merchant>(&merchant_key) else { continue; };
+        shift_trust(env, &voucher, &mut data, -record.weight * VOUCH_SLASH_FACTOR);
+        env.storage().persistent().set(&merchant_key, &data);
+    }
+    if end >= vouchers.len() {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, &end);
+    }
+}
+
+fn bzr_client(env: &Env) -> token::Client<'_> {
+    token::Client::new(env, &TrustContract::get_bzr_token(env.clone()).expect("BZR token not set"))
+}
+
+/// Drops purchased badges whose expiry has passed from `merchant.badges`.
+/// The expiry entry stays until the badge is bought again, so reads never need to save.
+fn prune_badges(env: &Env, user: &Address, merchant: &mut Merchant) {
+    let now = env.ledger().timestamp();
+    let mut kept = Vec::new(env);
+    for badge in merchant.badges.iter() {
+        let expiry: Option<u64> = env.storage().persistent().get(&DataKey::BadgeExpiry(user.clone(), badge.clone()));
+        if expiry.is_none_or(|expires_at| expires_at > now) { kept.push_back(badge); }
+    }
+    merchant.badges = kept;
+}
+
+/// Mints a reward within the supply cap and this epoch's budget and returns what was
+/// actually minted. Rewards shrink (possibly to 0) rather than failing the caller.
+fn mint_bzr(env: &Env, to: &Address, amount: i128, reason: MintReason) -> i128 {
+    let Some(bzr) = TrustContract::get_bzr_token(env.clone()) else { return 0; };
+    let stats = TrustContract::get_supply_stats(env.clone());
+    let amount = amount
+        .min(stats.cap - stats.total_minted)
+        .min(stats.epoch_budget - stats.epoch_minted);
+    if amount <= 0 { return 0; }
+
+    token::StellarAssetClient::new(env, &bzr).mint(to, &amount);
+    env.storage().instance().set(&DataKey::TotalMinted, &(stats.total_minted + amount));
+    env.storage().persistent().set(&DataKey::EpochMinted(stats.epoch), &(stats.epoch_minted + amount));
+    let by_reason = stats.minted_by_reason.get(reason).unwrap_or(0);
+    env.storage().persistent().set(&DataKey::MintedFor(reason), &(by_reason + amount));
+    amount
+}
+
+fn emission_epoch(env: &Env) -> u64 {
+    let start: u64 = env.storage().instance().get(&DataKey::EmissionStart).unwrap_or(env.ledger().timestamp());
+    env.ledger().timestamp().saturating_sub(start) / EMISSION_EPOCH
+}
+
+fn epoch_budget(epoch: u64) -> i128 {
+    INITIAL_EPOCH_BUDGET >> (epoch / EMISSION_HALVING_EPOCHS).min(127)
+}
+
+/// Halves a positive score once per elapsed half-life since the last activity.
+/// Negative scores never decay: going quiet doesn't launder a bad record.
+fn decay_score(env: &Env, merchant: &mut Merchant) {
+    let half_life = TrustContract::get_decay_half_life(env.clone());
+    if half_life == 0 || merchant.trust_score <= 0 { return; }
+    let periods = env.ledger().timestamp().saturating_sub(merchant.last_activity) / half_life;
+    if periods == 0 { return; }
+    merchant.trust_score >>= periods.min(31);
+    // Carry the partial period forward so repeated reads and writes decay at the same rate
+    merchant.last_activity += periods * half_life;
+}
+
+/// Applies pending decay and the Seal's revoke band to an in-memory copy, without
+/// publishing events; for queries that must agree with `get_trust`.
+fn decay_seal(env: &Env, merchant: &mut Merchant) {
+    decay_score(env, merchant);
+    if merchant.trust_score < SEAL_THRESHOLD - SEAL_HYSTERESIS {
+        if let Some(index) = merchant.badges.first_index_of(seal_badge()) { merchant.badges.remove(index); }
+    }
+}
+
+/// Settles decay on a merchant that is about to be saved outside `shift_trust`.
+fn apply_decay(env: &Env, user: &Address, merchant: &mut Merchant) {
+    decay_score(env, merchant);
+    update_seal(env, user, merchant);
+}
+
+/// Established merchants' vouches count for more; every eligible voucher counts at least once.
+fn vouch_weight(voucher_score: i32) -> i32 {
+    (voucher_score / VOUCH_WEIGHT_STEP).clamp(1, MAX_VOUCH_WEIGHT)
+}
+
+fn seal_badge() -> Symbol {
+    symbol_short!("seal")
+}
+
+/// Awards the Seal of Excellence at the threshold and revokes it below the hysteresis band.
+fn update_seal(env: &Env, user: &Address, merchant: &mut Merchant) {
+    let sealed = merchant.badges.first_index_of(seal_badge());
+    match sealed {
+        None if merchant.trust_score >= SEAL_THRESHOLD => {
+            merchant.badges.push_back(seal_badge());
+            SealChanged { merchant: user.clone(), awarded: true }.publish(env);
+        }
+        Some(index) if merchant.trust_score < SEAL_THRESHOLD - SEAL_HYSTERESIS => {
+            merchant.badges.remove(index);
+            SealChanged { merchant: user.clone(), awarded: false }.publish(env);
+        }
+        _ => {}
+    }
+}
+
+/// Exiles the merchant if their score or escrowed bond sits below the configured floors.
+fn check_exile(env: &Env, user: &Address, merchant: &mut Merchant) -> bool {
+    if merchant.status == MerchantStatus::Exiled { return false; }
+    let config = TrustContract::get_exile_config(env.clone());
+    // Only an escrowed bond is measured; a merchant who withdrew theirs holds none
+    let bond_short = merchant.bond_token.is_some() && merchant.bond_amount < config.bond_floor;
+    if merchant.trust_score < config.trust_floor || bond_short {
+        merchant.status = MerchantStatus::Exiled;
+        MerchantExiled {
+            merchant: user.clone(),
+            trust_score: merchant.trust_score,
+            bond_amount: merchant.bond_amount,
+        }.publish(env);
+        return true;
+    }
+    false
+}
+
+fn require_not_exiled(env: &Env, user: &Address) {
+    if TrustContract::is_exiled(env.clone(), user.clone()) { panic!("Merchant is exiled"); }
+}
+
+fn require_admin(env: &Env) -> Address {
+    let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Not initialized");
+    admin.require_auth();
+    admin
+}
+
+fn check_heir_shares(heirs: &Vec<HeirShare>) {
+    if heirs.is_empty() { panic!("At least one heir required"); }
+    let mut total: u32 = 0;
+    for (i, share) in heirs.iter().enumerate() {
+        if share.share_bps == 0 { panic!("Heir share must be positive"); }
+        if heirs.iter().skip(i + 1).any(|other| other.heir == share.heir) { panic!("Duplicate heir"); }
+        total += share.share_bps;
+    }
+    if total != BASIS_POINTS { panic!("Heir shares must add up to 100%"); }
+}
+
+fn heir_entitlement(vault: &LegacyVault, share: &HeirShare, token: &Address) -> i128 {
+    let total = vault.estate.get(token.clone()).unwrap_or(0);
+    total * share.share_bps as i128 / BASIS_POINTS as i128
+}
+
+fn vested_amount(env: &Env, vault: &LegacyVault, share: &HeirShare, token: &Address) -> i128 {
+    let entitlement = heir_entitlement(vault, share, token);
+    let schedule = &vault.release_schedule;
+    if schedule.tranches <= 1 { return entitlement; }
+    let elapsed = env.ledger().timestamp() - vault.settled_at;
+    let released = (elapsed / schedule.interval + 1).min(schedule.tranches as u64);
+    entitlement * released as i128 / schedule.tranches as i128
+}
+
+fn is_expired(env: &Env, emergency: &MedicalEmergency) -> bool {
+    emergency.status == EmergencyStatus::Active && env.ledger().timestamp() >= emergency.expires_at
+}
+
+fn require_open_emergency(env: &Env, emergency: &MedicalEmergency) {
+    if emergency.status != EmergencyStatus::Active { panic!("Emergency not active"); }
+    if is_expired(env, emergency) { panic!("Emergency expired"); }
+}
+
+/// Pays the emergency's payee from the vault, limited by what's left of the owner's cap and the vault balance.
+fn release_medical_funds(env: &Env, emergency: &MedicalEmergency) -> i128 {
+    let key = DataKey::Vault(emergency.target_user.clone());
+    let Some(mut vault) = env.storage().persistent().get::<_, LegacyVault>(&key) else { return 0; };
+    if vault.is_settled { return 0; }
+    let cap = vault.medical_caps.get(emergency.token.clone()).unwrap_or(0);
+    // The cap is cumulative, so repeated emergencies can't drain the vault one cap at a time
+    let released = vault.medical_released.get(emergency.token.clone()).unwrap_or(0);
+    let held = vault.assets.get(emergency.token.clone()).unwrap_or(0);
+    let amount = emergency.requested.min(cap - released).min(held);
+    if amount <= 0 { return 0; }
+
+    vault.assets.set(emergency.token.clone(), held - amount);
+    vault.medical_released.set(emergency.token.clone(), released + amount);
+    env.storage().persistent().set(&key, &vault);
+
+    let payouts_key = DataKey::MedicalPayouts(emergency.target_user.clone());
+    let mut payouts: Vec<MedicalPayout> = env.storage().persistent().get(&payouts_key).unwrap_or(Vec::new(env));
+    payouts.push_back(MedicalPayout {
+        payee: emergency.payee.clone(),
+        token: emergency.token.clone(),
+        amount,
+        timestamp: env.ledger().timestamp(),
+    });
+    env.storage().persistent().set(&payouts_key, &payouts);
+
+    token::Client::new(env, &emergency.token).transfer(&env.current_contract_address(), &emergency.payee, &amount);
+    MedicalFundsReleased {
+        owner: emergency.target_user.clone(),
+        payee: emergency.payee.clone(),
+        token: emergency.token.clone(),
+        amount,
+    }.publish(env);
+    amount
+}
+
+fn archive_emergency(env: &Env, emergency: MedicalEmergency) {
+    let key = DataKey::EmergencyHistory(emergency.target_user.clone());
+    let mut history: Vec<MedicalEmergency> = env.storage().persistent().get(&key).unwrap_or(Vec::new(env));
+    if history.len() >= MAX_EMERGENCY_HISTORY { history.pop_front(); }
+    history.push_back(emergency);
+    env.storage().persistent().set(&key, &history);
+}
+
+fn close_panic(env: &Env, user: &Address, mut event: PanicEvent, status: PanicStatus) {
+    event.status = status;
+    event.resolved_at = env.ledger().timestamp();
+    env.storage().persistent().remove(&DataKey::Panic(user.clone()));
+    env.storage().persistent().remove(&DataKey::PanicVotes(user.clone()));
+
+    let key = DataKey::PanicHistory(user.clone());
+    let mut history: Vec<PanicEvent> = env.storage().persistent().get(&key).unwrap_or(Vec::new(env));
+    if history.len() >= MAX_PANIC_HISTORY { history.pop_front(); }
+    history.push_back(event);
+    env.storage().persistent().set(&key, &history);
+}
+
+fn require_circle_ready(env: &Env, user: &Address, threshold: u32) {
+    if TrustContract::get_witnesses(env.clone(), user.clone()).len() < threshold { panic!("Circle not ready"); }
+}
+
+/// Removes `witness` from `user`'s circle (or pending invites) along with any votes they cast.
+fn exit_circle(env: &Env, user: &Address, witness: &Address) {
+    let invites_key = DataKey::WitnessInvites(user.clone());
+    let mut invites: Vec<Address> = env.storage().persistent().get(&invites_key).unwrap_or(Vec::new(env));
+    if let Some(index) = invites.first_index_of(witness.clone()) {
+        invites.remove(index);
+        env.storage().persistent().set(&invites_key, &invites);
+        return;
+    }
+
+    let circle_key = DataKey::Witnesses(user.clone());
+    let mut circle: Vec<Address> = env.storage().persistent().get(&circle_key).unwrap_or(Vec::new(env));
+    let index = circle.first_index_of(witness.clone()).expect("Not a witness");
+    circle.remove(index);
+    env.storage().persistent().set(&circle_key, &circle);
+
+    let removals_key = DataKey::PendingRemovals(user.clone());
+    let mut removals: Map<Address, u64> = env.storage().persistent().get(&removals_key).unwrap_or(Map::new(env));
+    if removals.remove(witness.clone()).is_some() {
+        env.storage().persistent().set(&removals_key, &removals);
+    }
+
+    let joined_key = DataKey::JoinedCircles(witness.clone());
+    let mut joined: Vec<Address> = env.storage().persistent().get(&joined_key).unwrap_or(Vec::new(env));
+    if let Some(index) = joined.first_index_of(user.clone()) {
+        joined.remove(index);
+        env.storage().persistent().set(&joined_key, &joined);
+    }
+
+    // Only sitting witnesses count toward thresholds
+    let emergency_key = DataKey::Emergency(user.clone());
+    if let Some(mut emergency) = env.storage().persistent().get::<_, MedicalEmergency>(&emergency_key) {
+        if emergency.status == EmergencyStatus::Active {
+            if let Some(index) = emergency.voters.first_index_of(witness.clone()) {
+                emergency.voters.remove(index);
+                env.storage().persistent().set(&emergency_key, &emergency);
+            }
+        }
+    }
+    let votes_key = DataKey::PanicVotes(user.clone());
+    let mut voters: Vec<Address> = env.storage().persistent().get(&votes_key).unwrap_or(Vec::new(env));
+    if let Some(index) = voters.first_index_of(witness.clone()) {
+        voters.remove(index);
+        env.storage().persistent().set(&votes_key, &voters);
+    }
+    let panic_key = DataKey::Panic(user.clone());
+    if let Some(mut event) = env.storage().persistent().get::<_, PanicEvent>(&panic_key) {
+        if let Some(index) = event.lift_voters.first_index_of(witness.clone()) {
+            event.lift_voters.remove(index);
+            env.storage().persistent().set(&panic_key, &event);
+        }
+    }
+}
+
+fn require_witness(env: &Env, witness: &Address, user: &Address) {
+    let circle: Vec<Address> = env.storage().persistent().get(&DataKey::Witnesses(user.clone())).unwrap_or(Vec::new(env));
+    if !circle.contains(witness.clone()) { panic!("Not a witness"); }
+}
+
+fn propose_vault_change(env: &Env, user: Address, change: VaultChange) {
+    let vault: LegacyVault = env.storage().persistent().get(&DataKey::Vault(user.clone())).expect("Vault not found");
+    if vault.is_settled { panic!("Vault already settled"); }
+    let pending_key = DataKey::PendingVaultChange(user.clone());
+    if env.storage().persistent().has(&pending_key) { panic!("Change already pending"); }
+
+    let effective_at = env.ledger().timestamp() + VAULT_CHANGE_DELAY;
+    let pending = PendingVaultChange { change: change.clone(), effective_at };
+    env.storage().persistent().set(&pending_key, &pending);
+
+    let witnesses: Vec<Address> = env.storage().persistent().get(&DataKey::Witnesses(user.clone())).unwrap_or(Vec::new(env));
+    VaultChangeProposed { owner: user, change, effective_at, witnesses }.publish(env);
+}
+
+#[cfg(test)]
+mod test;
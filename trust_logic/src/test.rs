@@ -1,476 +1,1476 @@
+#![allow(clippy::bool_assert_comparison)]
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use soroban_sdk::{
-        testutils::{Address as _, Ledger, LedgerInfo},
-        Address, Env,
+use super::*;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, Env,
+};
+
+fn create_contract(env: &Env) -> TrustContractClient<'static> {
+    let contract_id = env.register(TrustContract, ());
+    TrustContractClient::new(env, &contract_id)
+}
+
+#[test]
+#[should_panic]
+fn vouch_from_non_existent_profile_panics() {
+    let env = Env::default();
+    let client = create_contract(&env);
+
+    let voucher = Address::generate(&env);
+    let target = Address::generate(&env);
+
+    client.vouch(&voucher, &target);
+}
+
+#[test]
+fn test_stake_increases_score() {
+    let env = Env::default();
+    env.mock_all_auths(); // Mock signatures for testing
+    let client = create_contract(&env);
+    client.init(&Address::generate(&env));
+    let bond = setup_bond(&env, &client);
+    let user = Address::generate(&env);
+
+    assert_eq!(client.get_trust(&user), 0);
+    assert_eq!(client.is_bonded(&user), false);
+
+    stake(&client, &bond, &user, None);
+
+    assert_eq!(client.get_trust(&user), 10);
+    assert_eq!(client.is_bonded(&user), true);
+}
+
+#[test]
+fn test_decay() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let user = Address::generate(&env);
+    let admin = Address::generate(&env);
+
+    client.init(&admin);
+    let bond = setup_bond(&env, &client);
+
+    stake(&client, &bond, &user, None); // Score = 10
+    client.decay(&user); // Score = 7
+    assert_eq!(client.get_trust(&user), 7);
+}
+
+#[test]
+#[should_panic(expected = "Bond is still locked")]
+fn test_withdraw_too_early_panics() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    // Set mock time
+    env.ledger().with_mut(|li| { li.timestamp = 1000; });
+
+    let client = create_contract(&env);
+    client.init(&Address::generate(&env));
+    let bond = setup_bond(&env, &client);
+    let user = Address::generate(&env);
+
+    stake(&client, &bond, &user, None);
+
+    // Try to withdraw immediately (should fail)
+    client.withdraw(&user);
+}
+
+#[test]
+fn test_vouch_rewards_bzr() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let admin = Address::generate(&env);
+    let voucher = Address::generate(&env);
+    let target = Address::generate(&env);
+
+    // Setup: rewards are minted through the BZR token, and both sides must be bonded
+    client.init(&admin);
+    let bond = setup_bond(&env, &client);
+    create_bzr(&env, &client);
+    stake(&client, &bond, &voucher, None);
+    stake(&client, &bond, &target, None);
+
+    client.vouch(&voucher, &target);
+    assert_eq!(client.get_balance(&voucher), 5);
+}
+
+#[test]
+fn test_transfer_bzr() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let admin = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let target1 = Address::generate(&env);
+    let target2 = Address::generate(&env);
+
+    // Setup: one vouch per pair, and targets must be bonded
+    client.init(&admin);
+    let bond = setup_bond(&env, &client);
+    create_bzr(&env, &client);
+    stake(&client, &bond, &user1, None);
+    stake(&client, &bond, &target1, None);
+    stake(&client, &bond, &target2, None);
+
+    client.vouch(&user1, &target1); // user1 gets 5
+    client.vouch(&user1, &target2); // user1 gets 5 (total 10)
+    assert!(client.try_vouch(&user1, &Address::generate(&env)).is_err()); // Unknown target
+
+    client.transfer_bzr(&user1, &user2, &3);
+
+    assert_eq!(client.get_balance(&user1), 7);
+    assert_eq!(client.get_balance(&user2), 3);
+}
+
+#[test]
+fn test_shop_buy_badge() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    client.init(&admin);
+    let bond = setup_bond(&env, &client);
+    create_bzr(&env, &client);
+    stake(&client, &bond, &user, None);
+    for _ in 0..10 {
+        let target = Address::generate(&env);
+        stake(&client, &bond, &target, None);
+        client.vouch(&user, &target);
+    } // Earn 50 BZR
+
+    let badge = symbol_short!("verified");
+    client.list_badge(&badge, &BadgeListing { price: 50, stock: None, duration: None, min_trust: None, burn: true });
+    client.buy_badge(&user, &badge);
+
+    assert_eq!(client.has_badge(&user, &badge), true);
+    assert_eq!(client.get_balance(&user), 0);
+}
+
+#[test]
+fn test_crowdfund() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let merchant = Address::generate(&env);
+
+    client.init(&admin);
+    let bond = setup_bond(&env, &client);
+    create_bzr(&env, &client);
+    stake(&client, &bond, &user, None);
+    stake(&client, &bond, &merchant, None);
+
+    // Earn BZR
+    for _ in 0..4 {
+        let target = Address::generate(&env);
+        stake(&client, &bond, &target, None);
+        client.vouch(&user, &target);
+    } // 20 BZR
+
+    let id = client.create_campaign(&merchant, &100, &1000);
+    client.contribute(&user, &id, &15);
+
+    assert_eq!(client.get_balance(&user), 5);
+    assert_eq!(client.get_campaign(&id).raised, 15);
+}
+
+#[test]
+fn test_referral() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let referrer = Address::generate(&env);
+
+    client.init(&admin);
+    let bond = setup_bond(&env, &client);
+    create_bzr(&env, &client);
+    stake(&client, &bond, &referrer, None); // Referrer must exist
+    stake(&client, &bond, &user, Some(referrer.clone()));
+    assert_eq!(client.get_balance(&referrer), 0); // Paid only after the observation window
+
+    env.ledger().with_mut(|li| li.timestamp += 604_800);
+    client.claim_referral_reward(&user);
+    assert_eq!(client.get_balance(&referrer), 10);
+}
+
+#[test]
+fn test_dispute() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    client.init(&Address::generate(&env));
+    let bond = setup_bond(&env, &client);
+    let accuser = Address::generate(&env);
+    let target = Address::generate(&env);
+
+    stake(&client, &bond, &target, None); // Target must exist
+
+    assert_eq!(client.is_disputed(&target), false);
+    client.raise_dispute(&accuser, &target);
+    assert_eq!(client.is_disputed(&target), true);
+}
+
+#[test]
+fn test_resolve_dispute() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let admin = Address::generate(&env);
+    let accuser = Address::generate(&env);
+    let target = Address::generate(&env);
+
+    client.init(&admin);
+    let bond = setup_bond(&env, &client);
+    stake(&client, &bond, &target, None); // Target must exist
+
+    client.raise_dispute(&accuser, &target);
+    assert_eq!(client.is_disputed(&target), true);
+
+    client.resolve_dispute(&target);
+    assert_eq!(client.is_disputed(&target), false);
+}
+
+#[test]
+fn test_chat() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    client.init(&Address::generate(&env));
+    let bond = setup_bond(&env, &client);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    stake(&client, &bond, &user2, None); // Receiver must exist
+
+    client.send_message(&user1, &user2, &soroban_sdk::String::from_str(&env, "Hello!"));
+    let msgs = client.get_messages(&user2);
+    assert_eq!(msgs.len(), 1);
+    assert_eq!(msgs.get(0).unwrap().text, soroban_sdk::String::from_str(&env, "Hello!"));
+}
+
+#[test]
+fn test_poverty_observation_window() {
+    let mut po = PovertyObservation {
+        start_time: 0,
+        is_active: false,
     };
 
-    fn create_contract(env: &Env) -> TrustContractClient<'static> {
-        let contract_id = env.register_contract(None, TrustContract);
-        TrustContractClient::new(env, &contract_id)
-    }
+    let current_time = 1000;
+    po.start_observation(current_time);
 
-    #[test]
-    #[should_panic]
-    fn vouch_from_non_existent_profile_panics() {
-        let env = Env::default();
-        let client = create_contract(&env);
+    // Check before 7 days (604800 seconds)
+    assert_eq!(po.verify_window(current_time + 604799), false);
+    assert_eq!(po.is_active, true);
 
-        let voucher = Address::random(&env);
-        let target = Address::random(&env);
+    // Check after 7 days
+    assert_eq!(po.verify_window(current_time + 604800), true);
+    assert_eq!(po.is_active, false);
+}
 
-        client.vouch(&voucher, &target);
-    }
+#[test]
+fn test_poverty_observation_enforcement() {
+    let env = Env::default();
+    env.mock_all_auths();
 
-    #[test]
-    fn test_stake_increases_score() {
-        let env = Env::default();
-        env.mock_all_auths(); // Mock signatures for testing
-        let client = create_contract(&env);
-        let user = Address::random(&env);
-
-        assert_eq!(client.get_trust(&user), 0);
-        assert_eq!(client.is_bonded(&user), false);
-        
-        client.stake(&user, &None);
-        
-        assert_eq!(client.get_trust(&user), 10);
-        assert_eq!(client.is_bonded(&user), true);
-    }
+    let client = create_contract(&env);
+    client.init(&Address::generate(&env));
+    let bond = setup_bond(&env, &client);
+    let user = Address::generate(&env);
 
-    #[test]
-    fn test_decay() {
-        let env = Env::default();
-        env.mock_all_auths();
-        let client = create_contract(&env);
-        let user = Address::random(&env);
-        let admin = Address::random(&env);
+    // Set initial time
+    env.ledger().with_mut(|li| { li.timestamp = 1000; });
 
-        client.init(&admin);
+    // Stake bond (starts 7-day timer)
+    stake(&client, &bond, &user, None);
 
-        client.stake(&user, &None); // Score = 10
-        client.add_trust(&user);    // Score = 11
-        client.decay(&user);        // Score = 8
-        assert_eq!(client.get_trust(&user), 8);
-    }
+    // Attempt verification immediately (Should Fail)
+    assert_eq!(client.verify_status(&user), false);
+
+    // Warp time forward 7 days (604800 seconds) + 1 second
+    env.ledger().with_mut(|li| { li.timestamp = 1000 + 604800 + 1; });
+
+    // Attempt verification again (Should Pass)
+    assert_eq!(client.verify_status(&user), true);
+}
 
-    #[test]
-    #[should_panic(expected = "Bond is still locked")]
-    fn test_withdraw_too_early_panics() {
-        let env = Env::default();
-        env.mock_all_auths();
-        
-        // Set mock time
-        env.ledger().with_mut(|li| { li.timestamp = 1000; });
-
-        let client = create_contract(&env);
-        let user = Address::random(&env);
-
-        client.stake(&user, &None);
-        
-        // Try to withdraw immediately (should fail)
-        client.withdraw(&user);
+fn form_circle(client: &TrustContractClient, owner: &Address, witnesses: &Vec<Address>) {
+    for witness in witnesses.iter() {
+        client.invite_witness(owner, &witness);
+        client.accept_witness(&witness, owner);
     }
+}
 
-    #[test]
-    fn test_transfer_admin() {
-        let env = Env::default();
-        env.mock_all_auths();
-        let client = create_contract(&env);
-        let admin = Address::random(&env);
-        let new_admin = Address::random(&env);
+fn create_token<'a>(env: &Env, holder: &Address, amount: i128) -> token::Client<'a> {
+    let sac = env.register_stellar_asset_contract_v2(Address::generate(env));
+    token::StellarAssetClient::new(env, &sac.address()).mint(holder, &amount);
+    token::Client::new(env, &sac.address())
+}
 
-        client.init(&admin);
-        assert_eq!(client.get_admin(), admin);
+const BOND: i128 = 200;
 
-        client.transfer_admin(&new_admin);
-        assert_eq!(client.get_admin(), new_admin);
-    }
+/// Configures a bond in a fresh asset. The contract's admin must already be set.
+fn setup_bond<'a>(env: &Env, client: &TrustContractClient) -> token::StellarAssetClient<'a> {
+    let sac = env.register_stellar_asset_contract_v2(Address::generate(env));
+    client.set_bond_config(&sac.address(), &BOND, &2_592_000);
+    token::StellarAssetClient::new(env, &sac.address())
+}
 
-    #[test]
-    fn test_force_unbond() {
-        let env = Env::default();
-        env.mock_all_auths();
-        let client = create_contract(&env);
-        let admin = Address::random(&env);
-        let user = Address::random(&env);
-
-        client.init(&admin);
-        client.stake(&user, &None);
-        assert_eq!(client.is_bonded(&user), true);
-
-        // Admin forces unbond
-        client.force_unbond(&user);
-        assert_eq!(client.is_bonded(&user), false);
-        assert_eq!(client.get_trust(&user), 0); // Score drops back
-    }
+/// Funds `user` with the bond and stakes it.
+fn stake(client: &TrustContractClient, bond: &token::StellarAssetClient, user: &Address, referrer: Option<Address>) {
+    bond.mint(user, &BOND);
+    client.stake(user, &referrer);
+}
 
-    #[test]
-    fn test_vouch_rewards_bzr() {
-        let env = Env::default();
-        env.mock_all_auths();
-        let client = create_contract(&env);
-        let voucher = Address::random(&env);
-        let target = Address::random(&env);
+#[test]
+fn test_claim_legacy_transfers_assets_to_heir() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let owner = Address::generate(&env);
+    let heir = Address::generate(&env);
+    let pi = create_token(&env, &owner, 1_000);
 
-        // Setup: Voucher and Target must exist
-        client.stake(&voucher, &None);
-        client.stake(&target, &None);
+    client.create_vault(&owner, &vec![&env, HeirShare { heir: heir.clone(), share_bps: 10_000 }]);
+    client.deposit_to_vault(&owner, &pi.address, &600);
+    assert_eq!(client.get_vault_balance(&owner, &pi.address), 600);
 
-        client.vouch(&voucher, &target);
-        assert_eq!(client.get_balance(&voucher), 5);
-    }
+    env.ledger().with_mut(|li| { li.timestamp += 15_552_000; });
+    client.claim_legacy(&owner, &heir);
 
-    #[test]
-    fn test_transfer_bzr() {
-        let env = Env::default();
-        env.mock_all_auths();
-        let client = create_contract(&env);
-        let user1 = Address::random(&env);
-        let user2 = Address::random(&env);
-        let target = Address::random(&env);
+    assert_eq!(pi.balance(&heir), 600);
+    assert_eq!(client.get_vault_balance(&owner, &pi.address), 0);
+}
 
-        // Setup
-        client.stake(&user1, &None);
-        client.stake(&user2, &None); // Receiver must exist
-        client.stake(&target, &None);
+#[test]
+#[should_panic(expected = "Share already claimed")]
+fn test_claim_legacy_twice_panics() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let owner = Address::generate(&env);
+    let heir = Address::generate(&env);
+    let pi = create_token(&env, &owner, 1_000);
+
+    client.create_vault(&owner, &vec![&env, HeirShare { heir: heir.clone(), share_bps: 10_000 }]);
+    client.deposit_to_vault(&owner, &pi.address, &600);
+
+    env.ledger().with_mut(|li| { li.timestamp += 15_552_000; });
+    client.claim_legacy(&owner, &heir);
+    client.claim_legacy(&owner, &heir);
+}
 
-        client.vouch(&user1, &target); // user1 gets 5
-        client.vouch(&user1, &target); // user1 gets 5 (total 10)
+#[test]
+fn test_multi_heir_shares_claimed_independently() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let owner = Address::generate(&env);
+    let spouse = Address::generate(&env);
+    let child1 = Address::generate(&env);
+    let child2 = Address::generate(&env);
+    let pi = create_token(&env, &owner, 1_000);
+
+    let heirs = vec![
+        &env,
+        HeirShare { heir: spouse.clone(), share_bps: 5_000 },
+        HeirShare { heir: child1.clone(), share_bps: 3_000 },
+        HeirShare { heir: child2.clone(), share_bps: 2_000 },
+    ];
+    client.create_vault(&owner, &heirs);
+    client.deposit_to_vault(&owner, &pi.address, &1_000);
+
+    env.ledger().with_mut(|li| { li.timestamp += 15_552_000; });
+    client.claim_legacy(&owner, &spouse);
+    client.claim_legacy(&owner, &child2);
+
+    assert_eq!(pi.balance(&spouse), 500);
+    assert_eq!(pi.balance(&child2), 200);
+    // child1 has not claimed yet: their share stays locked in the vault
+    assert_eq!(client.get_vault_balance(&owner, &pi.address), 300);
+}
 
-        client.transfer_bzr(&user1, &user2, &3);
+#[test]
+#[should_panic(expected = "Heir shares must add up to 100%")]
+fn test_heir_shares_must_total_100_percent() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let owner = Address::generate(&env);
+
+    let heirs = vec![
+        &env,
+        HeirShare { heir: Address::generate(&env), share_bps: 5_000 },
+        HeirShare { heir: Address::generate(&env), share_bps: 3_000 },
+    ];
+    client.create_vault(&owner, &heirs);
+}
 
-        assert_eq!(client.get_balance(&user1), 7);
-        assert_eq!(client.get_balance(&user2), 3);
-    }
+#[test]
+fn test_update_heir_applies_after_delay() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let owner = Address::generate(&env);
+    let heir = Address::generate(&env);
+    let new_heir = Address::generate(&env);
+    let pi = create_token(&env, &owner, 1_000);
+
+    client.create_vault(&owner, &vec![&env, HeirShare { heir: heir.clone(), share_bps: 10_000 }]);
+    client.deposit_to_vault(&owner, &pi.address, &1_000);
+    client.update_heir(&owner, &vec![&env, HeirShare { heir: new_heir.clone(), share_bps: 10_000 }]);
+    assert!(client.get_pending_vault_change(&owner).is_some());
+    assert!(client.try_confirm_vault_change(&owner).is_err()); // Still time-locked
+
+    env.ledger().with_mut(|li| { li.timestamp += 604_800; });
+    client.confirm_vault_change(&owner);
+    assert!(client.get_pending_vault_change(&owner).is_none());
+
+    env.ledger().with_mut(|li| { li.timestamp += 15_552_000; });
+    assert!(client.try_claim_legacy(&owner, &heir).is_err());
+    client.claim_legacy(&owner, &new_heir);
+    assert_eq!(pi.balance(&new_heir), 1_000);
+}
 
-    #[test]
-    fn test_shop_buy_badge() {
-        let env = Env::default();
-        env.mock_all_auths();
-        let client = create_contract(&env);
-        let user = Address::random(&env);
-        let target = Address::random(&env);
-
-        client.stake(&user, &None);
-        client.stake(&target, &None);
-
-        for _ in 0..10 { client.vouch(&user, &target); } // Earn 50 BZR
-        
-        let badge = symbol_short!("verified");
-        client.buy_badge(&user, &badge);
-        
-        assert_eq!(client.has_badge(&user, &badge), true);
-        assert_eq!(client.get_balance(&user), 0);
-    }
+#[test]
+#[should_panic(expected = "No pending change")]
+fn test_witness_vetoes_heir_change() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let owner = Address::generate(&env);
+    let witness = Address::generate(&env);
+    let thief = Address::generate(&env);
+
+    client.create_vault(&owner, &vec![&env, HeirShare { heir: Address::generate(&env), share_bps: 10_000 }]);
+    form_circle(&client, &owner, &vec![&env, witness.clone()]);
+    client.update_heir(&owner, &vec![&env, HeirShare { heir: thief, share_bps: 10_000 }]);
+    client.veto_vault_change(&witness, &owner);
+
+    env.ledger().with_mut(|li| { li.timestamp += 604_800; });
+    client.confirm_vault_change(&owner);
+}
 
-    #[test]
-    fn test_crowdfund() {
-        let env = Env::default();
-        env.mock_all_auths();
-        let client = create_contract(&env);
-        let user = Address::random(&env);
-        let target = Address::random(&env);
+#[test]
+fn test_witness_vetoes_vault_withdrawal() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let owner = Address::generate(&env);
+    let witness = Address::generate(&env);
+    let pi = create_token(&env, &owner, 1_000);
+
+    client.create_vault(&owner, &vec![&env, HeirShare { heir: Address::generate(&env), share_bps: 10_000 }]);
+    client.deposit_to_vault(&owner, &pi.address, &1_000);
+    form_circle(&client, &owner, &vec![&env, witness.clone()]);
+    assert!(client.try_withdraw_from_vault(&owner, &pi.address, &1_001).is_err());
+
+    // A stolen key tries to empty the vault; nothing moves before the delay
+    client.withdraw_from_vault(&owner, &pi.address, &1_000);
+    assert!(client.try_confirm_vault_change(&owner).is_err());
+    assert_eq!(client.get_vault_balance(&owner, &pi.address), 1_000);
+
+    client.veto_vault_change(&witness, &owner);
+    env.ledger().with_mut(|li| { li.timestamp += 604_800; });
+    assert!(client.try_confirm_vault_change(&owner).is_err());
+    assert_eq!(pi.balance(&owner), 0);
+}
 
-        client.stake(&user, &None);
-        client.stake(&target, &None);
+#[test]
+fn test_heartbeat_delegate_refreshes_and_can_be_revoked() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let owner = Address::generate(&env);
+    let heir = Address::generate(&env);
+    let phone = Address::generate(&env);
 
-        // Earn BZR
-        for _ in 0..4 { client.vouch(&user, &target); } // 20 BZR
+    client.create_vault(&owner, &vec![&env, HeirShare { heir: heir.clone(), share_bps: 10_000 }]);
+    client.add_heartbeat_delegate(&owner, &phone);
 
-        client.deposit_crowdfund(&user, &15);
-        
-        assert_eq!(client.get_balance(&user), 5);
-        assert_eq!(client.get_crowdfund_balance(), 15);
-    }
+    env.ledger().with_mut(|li| { li.timestamp += 15_000_000; });
+    client.delegate_heartbeat(&phone, &owner);
 
-    #[test]
-    fn test_referral() {
-        let env = Env::default();
-        env.mock_all_auths();
-        let client = create_contract(&env);
-        let user = Address::random(&env);
-        let referrer = Address::random(&env);
-
-        client.stake(&user, &Some(referrer.clone()));
-        
-        assert_eq!(client.get_balance(&referrer), 10);
-    }
+    env.ledger().with_mut(|li| { li.timestamp += 1_000_000; });
+    assert!(client.try_claim_legacy(&owner, &heir).is_err()); // Delegate kept the owner alive
 
-    #[test]
-    fn test_voting() {
-        let env = Env::default();
-        env.mock_all_auths();
-        let client = create_contract(&env);
-        let user = Address::random(&env);
-        let target = Address::random(&env);
+    client.revoke_heartbeat_delegate(&owner, &phone);
+    assert!(client.try_delegate_heartbeat(&phone, &owner).is_err());
+    assert_eq!(client.get_heartbeat_delegates(&owner).len(), 0);
+}
 
-        client.stake(&user, &None);
-        client.stake(&target, &None);
+#[test]
+fn test_staged_release_vests_monthly() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let owner = Address::generate(&env);
+    let heir = Address::generate(&env);
+    let pi = create_token(&env, &owner, 2_400);
+
+    client.create_vault(&owner, &vec![&env, HeirShare { heir: heir.clone(), share_bps: 10_000 }]);
+    client.deposit_to_vault(&owner, &pi.address, &2_400);
+    client.update_release_schedule(&owner, &ReleaseSchedule { tranches: 24, interval: 2_592_000 });
+    env.ledger().with_mut(|li| { li.timestamp += 604_800; });
+    client.confirm_vault_change(&owner);
+
+    env.ledger().with_mut(|li| { li.timestamp += 15_552_000; });
+    client.claim_legacy(&owner, &heir); // First tranche at settlement
+    assert_eq!(pi.balance(&heir), 100);
+    assert!(client.try_claim_legacy(&owner, &heir).is_err()); // Nothing new vested
+
+    env.ledger().with_mut(|li| { li.timestamp += 2 * 2_592_000; });
+    assert_eq!(client.get_vested_amount(&owner, &heir, &pi.address), 300);
+    client.claim_legacy(&owner, &heir);
+    assert_eq!(client.get_claimed_amount(&owner, &heir, &pi.address), 300);
+
+    env.ledger().with_mut(|li| { li.timestamp += 30 * 2_592_000; });
+    client.claim_legacy(&owner, &heir);
+    assert_eq!(pi.balance(&heir), 2_400);
+}
 
-        // Earn 10 BZR
-        client.vouch(&user, &target); 
-        client.vouch(&user, &target);
+#[test]
+fn test_two_witness_circle_thresholds() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let owner = Address::generate(&env);
+    let w1 = Address::generate(&env);
+    let w2 = Address::generate(&env);
+
+    client.create_vault(&owner, &vec![&env, HeirShare { heir: Address::generate(&env), share_bps: 10_000 }]);
+    form_circle(&client, &owner, &vec![&env, w1.clone(), w2.clone()]);
+    // The default 3-of-5 is kept, so two witnesses can't act yet
+    assert_eq!(client.get_circle_config(&owner).panic_threshold, 3);
+    assert!(client.try_panic_button(&w1, &owner).is_err());
+    assert!(client.try_set_circle_config(&owner, &4, &5, &2).is_err());
+    assert!(client.try_set_circle_config(&owner, &4, &0, &2).is_err());
+    assert!(client.try_set_circle_config(&owner, &1, &1, &1).is_err()); // Two seats are taken
+
+    client.set_circle_config(&owner, &4, &2, &1);
+    let config = client.get_circle_config(&owner);
+    assert_eq!((config.max_size, config.medical_threshold, config.panic_threshold), (4, 2, 1));
+
+    // The owner can't drop a witness below the 2-of-2 medical threshold
+    client.remove_witness(&owner, &w2);
+    env.ledger().with_mut(|li| { li.timestamp += 604_800; });
+    assert!(client.try_confirm_witness_removal(&owner, &w2).is_err());
+
+    // But a witness may always leave, and the medical vote is then out of reach
+    client.leave_circle(&w2, &owner);
+    assert_eq!(client.get_witnesses(&owner), vec![&env, w1.clone()]);
+    assert_eq!(client.get_pending_removals(&owner).len(), 0);
+    client.declare_emergency(&owner, &owner, &owner, &Address::generate(&env), &1);
+    assert!(client.try_witness_vote_medical(&w1, &owner).is_err());
+    client.panic_button(&w1, &owner);
+}
 
-        client.vote(&user, &1, &true); // Vote Yes on Prop 1
-        assert_eq!(client.get_proposal_stats(&1), (10, 0));
-    }
+#[test]
+fn test_circle_configured_before_invites() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let owner = Address::generate(&env);
+    let w1 = Address::generate(&env);
+    let w2 = Address::generate(&env);
+
+    client.create_vault(&owner, &vec![&env, HeirShare { heir: Address::generate(&env), share_bps: 10_000 }]);
+    client.set_circle_config(&owner, &3, &2, &2);
+    form_circle(&client, &owner, &vec![&env, w1.clone()]);
+    assert!(client.try_panic_button(&w1, &owner).is_err()); // Not ready with one witness
+
+    form_circle(&client, &owner, &vec![&env, w2.clone()]);
+    client.panic_button(&w1, &owner);
+    client.panic_button(&w2, &owner);
+    assert_eq!(client.get_panic(&owner).unwrap().status, PanicStatus::Active);
+}
 
-    #[test]
-    fn test_create_proposal() {
-        let env = Env::default();
-        env.mock_all_auths();
-        let client = create_contract(&env);
-        let user = Address::random(&env);
-        let target = Address::random(&env);
+#[test]
+fn test_witness_cannot_vote_twice() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let owner = Address::generate(&env);
+    let w1 = Address::generate(&env);
+    let w2 = Address::generate(&env);
+    let w3 = Address::generate(&env);
+    let hospital = Address::generate(&env);
+    let pi = create_token(&env, &owner, 1_000);
+
+    client.create_vault(&owner, &vec![&env, HeirShare { heir: Address::generate(&env), share_bps: 10_000 }]);
+    form_circle(&client, &owner, &vec![&env, w1.clone(), w2.clone(), w3.clone()]);
+    client.declare_emergency(&owner, &owner, &hospital, &pi.address, &100);
+
+    client.witness_vote_medical(&w1, &owner);
+    assert!(client.try_witness_vote_medical(&w1, &owner).is_err());
+    client.panic_button(&w1, &owner);
+    assert!(client.try_panic_button(&w1, &owner).is_err());
+
+    // A withdrawn vote no longer counts
+    client.withdraw_medical_vote(&w1, &owner);
+    client.witness_vote_medical(&w2, &owner);
+    client.witness_vote_medical(&w3, &owner);
+    assert_eq!(client.get_emergency(&owner).unwrap().status, EmergencyStatus::Active);
+
+    client.witness_vote_medical(&w1, &owner);
+    assert_eq!(client.get_emergency(&owner).unwrap().status, EmergencyStatus::Unlocked);
+    assert!(client.try_withdraw_medical_vote(&w1, &owner).is_err());
+}
 
-        client.stake(&user, &None);
-        client.stake(&target, &None);
+#[test]
+fn test_emergency_lifecycle() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let owner = Address::generate(&env);
+    let w1 = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    let hospital = Address::generate(&env);
+    let pi = create_token(&env, &owner, 1_000);
+
+    form_circle(&client, &owner, &vec![&env, w1.clone()]);
+    client.set_circle_config(&owner, &5, &1, &1);
+    assert!(client.try_declare_emergency(&stranger, &owner, &hospital, &pi.address, &100).is_err());
+
+    // Owner cancels a false alarm
+    client.declare_emergency(&w1, &owner, &hospital, &pi.address, &100);
+    client.cancel_emergency(&owner);
+
+    // An unattended emergency expires and no longer blocks a new one
+    client.declare_emergency(&w1, &owner, &hospital, &pi.address, &100);
+    env.ledger().with_mut(|li| { li.timestamp += 259_200; });
+    assert!(client.try_witness_vote_medical(&w1, &owner).is_err());
+    client.declare_emergency(&w1, &owner, &hospital, &pi.address, &100);
+
+    client.witness_vote_medical(&w1, &owner);
+    client.resolve_emergency(&w1, &owner);
+    assert!(client.get_emergency(&owner).is_none());
+
+    let history = client.get_emergency_history(&owner);
+    assert_eq!(history.len(), 3);
+    assert_eq!(history.get(0).unwrap().status, EmergencyStatus::Cancelled);
+    assert_eq!(history.get(1).unwrap().status, EmergencyStatus::Expired);
+    assert_eq!(history.get(2).unwrap().status, EmergencyStatus::Resolved);
+}
 
-        // Earn BZR (Need 100)
-        for _ in 0..20 { client.vouch(&user, &target); } 
+#[test]
+fn test_medical_release_is_capped_and_logged() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let owner = Address::generate(&env);
+    let w1 = Address::generate(&env);
+    let w2 = Address::generate(&env);
+    let hospital = Address::generate(&env);
+    let pi = create_token(&env, &owner, 1_000);
+
+    client.create_vault(&owner, &vec![&env, HeirShare { heir: Address::generate(&env), share_bps: 10_000 }]);
+    client.deposit_to_vault(&owner, &pi.address, &1_000);
+    client.set_medical_cap(&owner, &pi.address, &250);
+    form_circle(&client, &owner, &vec![&env, w1.clone(), w2.clone()]);
+    client.set_circle_config(&owner, &5, &2, &2);
+
+    client.declare_emergency(&w1, &owner, &hospital, &pi.address, &400);
+    client.witness_vote_medical(&w1, &owner);
+    assert_eq!(pi.balance(&hospital), 0);
+    client.witness_vote_medical(&w2, &owner);
+
+    assert_eq!(pi.balance(&hospital), 250);
+    assert_eq!(client.get_vault_balance(&owner, &pi.address), 750);
+    assert_eq!(client.get_emergency(&owner).unwrap().released, 250);
+    let payouts = client.get_medical_payouts(&owner);
+    assert_eq!(payouts.len(), 1);
+    assert_eq!(payouts.get(0).unwrap().payee, hospital);
+
+    // The cap spans emergencies: a second round releases nothing more
+    client.resolve_emergency(&w1, &owner);
+    client.declare_emergency(&w1, &owner, &hospital, &pi.address, &400);
+    client.witness_vote_medical(&w1, &owner);
+    client.witness_vote_medical(&w2, &owner);
+    assert_eq!(client.get_emergency(&owner).unwrap().released, 0);
+    assert_eq!(pi.balance(&hospital), 250);
+
+    // Only the owner restarts it
+    client.resolve_emergency(&w1, &owner);
+    client.set_medical_cap(&owner, &pi.address, &100);
+    client.declare_emergency(&w1, &owner, &hospital, &pi.address, &400);
+    client.witness_vote_medical(&w1, &owner);
+    client.witness_vote_medical(&w2, &owner);
+    assert_eq!(pi.balance(&hospital), 350);
+    assert_eq!(client.get_vault_balance(&owner, &pi.address), 650);
+}
 
-        let id = client.create_proposal(&user);
-        assert_eq!(id, 1);
-        assert_eq!(client.get_balance(&user), 0);
-    }
+#[test]
+fn test_circle_lifts_mistaken_panic() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| { li.timestamp = 20_000_000; });
+    let client = create_contract(&env);
+    let owner = Address::generate(&env);
+    let heir = Address::generate(&env);
+    let w1 = Address::generate(&env);
+    let w2 = Address::generate(&env);
+    let pi = create_token(&env, &owner, 1_000);
+
+    client.create_vault(&owner, &vec![&env, HeirShare { heir: heir.clone(), share_bps: 10_000 }]);
+    client.deposit_to_vault(&owner, &pi.address, &1_000);
+    form_circle(&client, &owner, &vec![&env, w1.clone(), w2.clone()]);
+    client.set_circle_config(&owner, &5, &2, &2);
+
+    client.panic_button(&w1, &owner);
+    client.panic_button(&w2, &owner);
+    assert_eq!(client.get_panic(&owner).unwrap().status, PanicStatus::Active);
+    assert_eq!(client.get_panic_votes(&owner).len(), 0);
+    assert!(client.try_panic_button(&w1, &owner).is_err()); // Already active
+
+    client.lift_panic(&w1, &owner);
+    client.lift_panic(&w2, &owner);
+    assert!(client.get_panic(&owner).is_none());
+    assert_eq!(client.get_panic_history(&owner).get(0).unwrap().status, PanicStatus::LiftedByCircle);
+
+    // Heartbeat is restored, so the heir can't claim 7 days later
+    env.ledger().with_mut(|li| { li.timestamp += 604_800; });
+    assert!(client.try_claim_legacy(&owner, &heir).is_err());
+    client.withdraw_from_vault(&owner, &pi.address, &1_000);
+    env.ledger().with_mut(|li| { li.timestamp += 604_800; });
+    client.confirm_vault_change(&owner);
+    assert_eq!(pi.balance(&owner), 1_000);
+}
 
-    #[test]
-    fn test_dispute() {
-        let env = Env::default();
-        env.mock_all_auths();
-        let client = create_contract(&env);
-        let accuser = Address::random(&env);
-        let target = Address::random(&env);
+#[test]
+#[should_panic(expected = "Panic cooling down")]
+fn test_panic_cooldown_after_owner_clears() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| { li.timestamp = 20_000_000; });
+    let client = create_contract(&env);
+    let owner = Address::generate(&env);
+    let w1 = Address::generate(&env);
+
+    client.create_vault(&owner, &vec![&env, HeirShare { heir: Address::generate(&env), share_bps: 10_000 }]);
+    form_circle(&client, &owner, &vec![&env, w1.clone()]);
+    client.set_circle_config(&owner, &5, &1, &1);
+
+    client.panic_button(&w1, &owner);
+    client.ping_heartbeat(&owner);
+    assert_eq!(client.get_panic_history(&owner).get(0).unwrap().status, PanicStatus::ClearedByOwner);
+    client.panic_button(&w1, &owner);
+}
 
-        client.stake(&target, &None); // Target must exist
+#[test]
+fn test_witness_must_accept_invite() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let owner = Address::generate(&env);
+    let other_owner = Address::generate(&env);
+    let witness = Address::generate(&env);
+
+    client.create_vault(&owner, &vec![&env, HeirShare { heir: Address::generate(&env), share_bps: 10_000 }]);
+    client.invite_witness(&owner, &witness);
+    assert!(client.try_panic_button(&witness, &owner).is_err()); // Invited but not accepted
+
+    client.accept_witness(&witness, &owner);
+    client.invite_witness(&other_owner, &witness);
+    client.accept_witness(&witness, &other_owner);
+    assert_eq!(client.get_joined_circles(&witness), vec![&env, owner.clone(), other_owner.clone()]);
+
+    client.leave_circle(&witness, &owner);
+    assert_eq!(client.get_witnesses(&owner).len(), 0);
+    assert_eq!(client.get_joined_circles(&witness), vec![&env, other_owner.clone()]);
+    assert!(client.try_panic_button(&witness, &owner).is_err());
+}
 
-        assert_eq!(client.is_disputed(&target), false);
-        client.raise_dispute(&accuser, &target);
-        assert_eq!(client.is_disputed(&target), true);
-    }
+#[test]
+fn test_stake_escrows_bond_until_unlock() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let pi = create_token(&env, &user, 500_000_000);
+
+    client.init(&admin);
+    client.set_bond_config(&pi.address, &200_000_000, &2_592_000); // 20 Pi for 30 days
+    client.stake(&user, &None);
+    assert_eq!(pi.balance(&user), 300_000_000);
+    assert_eq!(pi.balance(&client.address), 200_000_000);
+    assert!(client.try_withdraw(&user).is_err());
+
+    env.ledger().with_mut(|li| { li.timestamp += 2_592_000; });
+    client.withdraw(&user);
+    assert_eq!(pi.balance(&user), 500_000_000);
+    assert_eq!(client.is_bonded(&user), false);
+    assert_eq!(client.get_trust(&user), 0);
+}
 
-    #[test]
-    fn test_resolve_dispute() {
-        let env = Env::default();
-        env.mock_all_auths();
-        let client = create_contract(&env);
-        let admin = Address::random(&env);
-        let accuser = Address::random(&env);
-        let target = Address::random(&env);
+#[test]
+fn test_trust_variance_saturates_at_bounds() {
+    let env = Env::default();
+    let client = create_contract(&env);
+    let user = Address::generate(&env);
+    let mut merchant = new_merchant(&env, "User");
+
+    env.as_contract(&client.address, || {
+        adjust_trust(&env, &user, &mut merchant, -5_000);
+        assert_eq!(merchant.trust_score, -1987);
+        adjust_trust(&env, &user, &mut merchant, i32::MAX);
+        assert_eq!(merchant.trust_score, 2049);
+        adjust_trust(&env, &user, &mut merchant, -2049);
+        assert_eq!(merchant.trust_score, 0);
+    });
+}
 
-        client.init(&admin);
-        client.stake(&target, &None); // Target must exist
+#[test]
+fn test_exile_and_reinstate() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let peer = Address::generate(&env);
+
+    client.init(&admin);
+    let bond = setup_bond(&env, &client);
+    client.set_exile_config(&5, &0);
+    stake(&client, &bond, &peer, None);
+    stake(&client, &bond, &user, None); // Score 10
+    env.ledger().with_mut(|li| { li.timestamp += 2_592_000; });
+    client.withdraw(&user); // Score 0, below the floor of 5
+
+    assert_eq!(client.is_exiled(&user), true);
+    assert!(client.try_stake(&user, &None).is_err());
+    assert!(client.try_vouch(&user, &peer).is_err());
+    assert!(client.try_send_message(&user, &peer, &soroban_sdk::String::from_str(&env, "Hi")).is_err());
+
+    client.reinstate(&user);
+    assert_eq!(client.is_exiled(&user), false);
+    assert_eq!(client.get_trust(&user), 5);
+    stake(&client, &bond, &user, None);
+}
 
-        client.raise_dispute(&accuser, &target);
-        assert_eq!(client.is_disputed(&target), true);
+#[test]
+fn test_seal_awarded_with_hysteresis() {
+    let env = Env::default();
+    let client = create_contract(&env);
+    let user = Address::generate(&env);
+    let mut merchant = new_merchant(&env, "User");
+
+    env.as_contract(&client.address, || {
+        adjust_trust(&env, &user, &mut merchant, 2048);
+        assert!(!merchant.badges.contains(seal_badge()));
+        adjust_trust(&env, &user, &mut merchant, 1);
+        assert!(merchant.badges.contains(seal_badge()));
+        adjust_trust(&env, &user, &mut merchant, -100); // 1949: inside the band
+        assert!(merchant.badges.contains(seal_badge()));
+        adjust_trust(&env, &user, &mut merchant, -1);
+        assert!(!merchant.badges.contains(seal_badge()));
+    });
+}
 
-        client.resolve_dispute(&target);
-        assert_eq!(client.is_disputed(&target), false);
-    }
+#[test]
+fn test_observation_not_reset_by_restake() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| { li.timestamp = 1000; });
+    let client = create_contract(&env);
+    client.init(&Address::generate(&env));
+    let bond = setup_bond(&env, &client);
+    let user = Address::generate(&env);
+
+    stake(&client, &bond, &user, None);
+    env.ledger().with_mut(|li| { li.timestamp = 1000 + 604_799; });
+    assert_eq!(client.verify_status(&user), false);
+
+    env.ledger().with_mut(|li| { li.timestamp = 1000 + 2_592_000; });
+    client.withdraw(&user);
+    stake(&client, &bond, &user, None);
+    assert_eq!(client.verify_status(&user), true);
+}
 
-    #[test]
-    fn test_nickname() {
-        let env = Env::default();
-        env.mock_all_auths();
-        let client = create_contract(&env);
-        let user = Address::random(&env);
-
-        assert_eq!(client.get_nickname(&user), symbol_short!("User"));
-        
-        client.set_nickname(&user, &symbol_short!("BazaarMer"));
-        assert_eq!(client.get_nickname(&user), symbol_short!("BazaarMer"));
-    }
+#[test]
+fn test_vouch_requires_bond_and_counts_once() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    client.init(&Address::generate(&env));
+    let bond = setup_bond(&env, &client);
+    let voucher = Address::generate(&env);
+    let target = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    stake(&client, &bond, &target, None);
+    assert!(client.try_vouch(&stranger, &target).is_err()); // No profile
+    stake(&client, &bond, &voucher, None);
+    client.vouch(&voucher, &target);
+    assert_eq!(client.get_trust(&target), 11);
+    assert!(client.try_vouch(&voucher, &target).is_err()); // One vouch per pair
+
+    env.ledger().with_mut(|li| { li.timestamp += 2_592_000; });
+    client.withdraw(&voucher);
+    assert!(client.try_vouch(&voucher, &stranger).is_err()); // No longer bonded
+}
+
+#[test]
+fn test_vouch_weight_scales_with_voucher_score() {
+    assert_eq!(vouch_weight(-300), 1);
+    assert_eq!(vouch_weight(10), 1);
+    assert_eq!(vouch_weight(650), 3);
+    assert_eq!(vouch_weight(2049), 10);
+}
 
-    #[test]
-    fn test_nickname_search() {
-        let env = Env::default();
-        env.mock_all_auths();
-        let client = create_contract(&env);
-        let user = Address::random(&env);
-        let nick = symbol_short!("MerchantX");
+#[test]
+fn test_revoke_vouch_reverses_weight() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    client.init(&Address::generate(&env));
+    let bond = setup_bond(&env, &client);
+    let voucher = Address::generate(&env);
+    let target = Address::generate(&env);
+
+    stake(&client, &bond, &voucher, None);
+    stake(&client, &bond, &target, None);
+    client.vouch(&voucher, &target);
+    assert_eq!(client.get_vouch(&voucher, &target).unwrap().weight, 1);
+    assert_eq!(client.get_trust(&target), 11);
+
+    client.revoke_vouch(&voucher, &target);
+    assert_eq!(client.get_trust(&target), 10);
+    assert!(client.get_vouch(&voucher, &target).is_none());
+    assert_eq!(client.get_vouchers(&target).len(), 0);
+    assert!(client.try_vouch(&voucher, &target).is_err()); // No vouch -> revoke reward loops
+}
 
-        client.set_nickname(&user, &nick);
-        assert_eq!(client.get_address_by_nickname(&nick), Some(user));
-    }
+#[test]
+fn test_lost_dispute_slashes_vouchers() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let admin = Address::generate(&env);
+    let accuser = Address::generate(&env);
+    let voucher = Address::generate(&env);
+    let scammer = Address::generate(&env);
+
+    client.init(&admin);
+    let bond = setup_bond(&env, &client);
+    stake(&client, &bond, &voucher, None);
+    stake(&client, &bond, &scammer, None);
+    client.vouch(&voucher, &scammer);
+
+    client.raise_dispute(&accuser, &scammer);
+    assert_eq!(client.is_disputed(&scammer), true);
+    client.uphold_dispute(&scammer);
+
+    assert_eq!(client.is_disputed(&scammer), false);
+    assert_eq!(client.get_trust(&scammer), 11 - 50);
+    assert_eq!(client.is_exiled(&scammer), true);
+    assert_eq!(client.get_trust(&voucher), 10 - 2); // Weight 1, slashed twice over
+}
 
-    #[test]
-    #[should_panic(expected = "Maintenance mode active")]
-    fn test_maintenance_mode() {
-        let env = Env::default();
-        env.mock_all_auths();
-        let client = create_contract(&env);
-        let admin = Address::random(&env);
-        let user = Address::random(&env);
-        let target = Address::random(&env);
+#[test]
+fn test_voucher_cannot_revoke_during_dispute() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let admin = Address::generate(&env);
+    let accuser = Address::generate(&env);
+    let voucher = Address::generate(&env);
+    let scammer = Address::generate(&env);
+
+    client.init(&admin);
+    let bond = setup_bond(&env, &client);
+    stake(&client, &bond, &voucher, None);
+    stake(&client, &bond, &scammer, None);
+    client.vouch(&voucher, &scammer);
+
+    // Seeing the dispute coming, the voucher tries to pull out first
+    client.raise_dispute(&accuser, &scammer);
+    assert!(client.try_revoke_vouch(&voucher, &scammer).is_err());
+    client.uphold_dispute(&scammer);
+
+    assert_eq!(client.get_trust(&voucher), 10 - 2);
+    assert_eq!(client.is_exiled(&scammer), true);
+    assert!(client.try_revoke_vouch(&voucher, &scammer).is_err());
+}
 
-        client.init(&admin);
-        client.set_maintenance(&true);
+#[test]
+fn test_trust_decays_with_inactivity() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    client.init(&admin);
+    let bond = setup_bond(&env, &client);
+    client.set_decay_half_life(&1000);
+    stake(&client, &bond, &user, None);
+    assert_eq!(client.get_trust(&user), 10);
+
+    env.ledger().with_mut(|li| li.timestamp += 1999);
+    assert_eq!(client.get_trust(&user), 5); // One full half-life elapsed
+    env.ledger().with_mut(|li| li.timestamp += 1);
+    assert_eq!(client.get_trust(&user), 2);
+
+    // Decay is settled on write and the gain restarts the clock
+    let voucher = Address::generate(&env);
+    stake(&client, &bond, &voucher, None);
+    client.vouch(&voucher, &user);
+    assert_eq!(client.get_trust(&user), 3);
+}
 
-        client.vouch(&user, &target); // Should panic
-    }
+#[test]
+fn test_admin_decay_is_audited() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    client.init(&admin);
+    let bond = setup_bond(&env, &client);
+    stake(&client, &bond, &user, None);
+    client.decay(&user);
+
+    assert_eq!(client.get_trust(&user), 7);
+    let history = client.get_decay_history(&user);
+    assert_eq!(history.len(), 1);
+    assert_eq!(history.get(0).unwrap().admin, admin);
+    assert_eq!(history.get(0).unwrap().score_after, 7);
+}
 
-    #[test]
-    fn test_chat() {
-        let env = Env::default();
-        env.mock_all_auths();
-        let client = create_contract(&env);
-        let user1 = Address::random(&env);
-        let user2 = Address::random(&env);
+#[test]
+fn test_graph_score_blends_into_trust() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let admin = Address::generate(&env);
+    let oracle = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    client.init(&admin);
+    let bond = setup_bond(&env, &client);
+    stake(&client, &bond, &user, None);
+    client.set_oracle(&oracle, &2500); // Graph score counts for 25%
+
+    let mut scores = Map::new(&env);
+    scores.set(user.clone(), 410);
+    client.commit_graph_scores(&1, &scores);
+    assert_eq!(client.get_graph_score(&user).unwrap().epoch, 1);
+    assert_eq!(client.get_trust(&user), (10 * 3 + 410) / 4);
+
+    // Replaying an old epoch is rejected
+    assert!(client.try_commit_graph_scores(&1, &scores).is_err());
+
+    // Left out of the next epoch, the old score stops counting
+    client.commit_graph_scores(&2, &Map::new(&env));
+    assert!(client.get_graph_score(&user).is_none());
+    assert_eq!(client.get_trust(&user), 10);
+}
 
-        client.stake(&user2, &None); // Receiver must exist
+fn create_bzr(env: &Env, client: &TrustContractClient) -> bzr_token::BzrTokenClient<'static> {
+    let bzr = bzr_token::BzrTokenClient::new(env, &env.register(bzr_token::BzrToken, ()));
+    bzr.init(&client.address);
+    client.set_bzr_token(&bzr.address);
+    bzr
+}
 
-        client.send_message(&user1, &user2, &soroban_sdk::String::from_str(&env, "Hello!"));
-        let msgs = client.get_messages(&user2);
-        assert_eq!(msgs.len(), 1);
-        assert_eq!(msgs.get(0).unwrap().text, soroban_sdk::String::from_str(&env, "Hello!"));
-    }
+#[test]
+fn test_vouch_mints_bzr_through_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let admin = Address::generate(&env);
+    let voucher = Address::generate(&env);
+    let target = Address::generate(&env);
+    let friend = Address::generate(&env);
+
+    client.init(&admin);
+    let bond = setup_bond(&env, &client);
+    let bzr = create_bzr(&env, &client);
+    stake(&client, &bond, &voucher, None);
+    stake(&client, &bond, &target, None);
+    client.vouch(&voucher, &target);
+
+    assert_eq!(bzr.balance(&voucher), 5);
+    assert_eq!(client.get_balance(&voucher), 5);
+
+    client.transfer_bzr(&voucher, &friend, &3);
+    assert_eq!(bzr.balance(&friend), 3);
+    assert_eq!(client.get_balance(&voucher), 2);
+
+    // Only the TrustContract can mint
+    env.set_auths(&[]);
+    assert!(bzr.try_mint(&voucher, &100).is_err());
+}
 
-    #[test]
-    fn test_escrow_flow() {
-        let env = Env::default();
-        env.mock_all_auths();
-        let client = create_contract(&env);
-        let buyer = Address::random(&env);
-        let seller = Address::random(&env);
-        let target = Address::random(&env);
-
-        client.stake(&buyer, &None);
-        client.stake(&seller, &None); // Seller must exist to receive funds
-        client.stake(&target, &None);
-
-        for _ in 0..20 { client.vouch(&buyer, &target); } // Fund buyer with 100 BZR
-
-        let id = client.create_escrow(&buyer, &seller, &50);
-        client.approve_escrow(&id, &buyer); // Buyer approves
-        client.approve_escrow(&id, &seller); // Seller approves -> Release
-        
-        assert_eq!(client.get_balance(&seller), 50);
-    }
+#[test]
+fn test_supply_stats_track_mints() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let admin = Address::generate(&env);
+    let voucher = Address::generate(&env);
+    let target = Address::generate(&env);
+
+    client.init(&admin);
+    let bond = setup_bond(&env, &client);
+    create_bzr(&env, &client);
+    stake(&client, &bond, &voucher, None);
+    stake(&client, &bond, &target, None);
+    client.vouch(&voucher, &target);
+
+    let stats = client.get_supply_stats();
+    assert_eq!(stats.total_minted, 5);
+    assert_eq!(stats.epoch_minted, 5);
+    assert_eq!(stats.minted_by_reason.get(MintReason::Vouch), Some(5));
+    assert_eq!(stats.minted_by_reason.get(MintReason::Referral), Some(0));
+
+    // A year later the weekly budget has halved and the new epoch starts empty
+    env.ledger().with_mut(|li| li.timestamp += 52 * 604_800);
+    let stats = client.get_supply_stats();
+    assert_eq!(stats.epoch, 52);
+    assert_eq!(stats.epoch_budget, 25_000);
+    assert_eq!(stats.epoch_minted, 0);
+    assert_eq!(stats.total_minted, 5);
+}
 
-    #[test]
-    fn test_subscription() {
-        let env = Env::default();
-        env.mock_all_auths();
-        let client = create_contract(&env);
-        let user = Address::random(&env);
-        let target = Address::random(&env);
-
-        client.stake(&user, &None);
-        client.stake(&target, &None);
-
-        for _ in 0..10 { client.vouch(&user, &target); } // 50 BZR
-        
-        assert_eq!(client.is_subscribed(&user), false);
-        client.subscribe(&user);
-        assert_eq!(client.is_subscribed(&user), true);
-        assert_eq!(client.get_balance(&user), 0);
-    }
+#[test]
+fn test_mint_is_bounded_by_epoch_budget() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    client.init(&admin);
+    let bzr = create_bzr(&env, &client);
+    env.as_contract(&client.address, || {
+        assert_eq!(mint_bzr(&env, &user, 60_000, MintReason::Lottery), 50_000);
+        assert_eq!(mint_bzr(&env, &user, 1, MintReason::Lottery), 0);
+    });
+    assert_eq!(bzr.balance(&user), 50_000);
+}
 
-    #[test]
-    fn test_multisig() {
-        let env = Env::default();
-        env.mock_all_auths();
-        let client = create_contract(&env);
-        let user1 = Address::random(&env);
-        let user2 = Address::random(&env);
-        let target = Address::random(&env);
-
-        client.stake(&user1, &None);
-        client.stake(&target, &None);
-
-        for _ in 0..20 { client.vouch(&user1, &target); } // 100 BZR
-
-        let owners = soroban_sdk::vec![&env, user1.clone(), user2.clone()];
-        let wid = client.create_wallet(&user1, &owners, &2);
-        
-        client.deposit_wallet(&user1, &wid, &100);
-        let tx_id = client.propose_tx(&user1, &wid, &target, &50);
-        
-        // User1 approved implicitly or explicitly. Need User2.
-        client.approve_tx(&user2, &tx_id);
-        
-        assert_eq!(client.get_balance(&target), 50);
-    }
+#[test]
+fn test_referral_paid_after_observation() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let admin = Address::generate(&env);
+    let referrer = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    client.init(&admin);
+    let bond = setup_bond(&env, &client);
+    stake(&client, &bond, &referrer, None);
+    assert!(client.try_stake(&user, &Some(user.clone())).is_err());
+    stake(&client, &bond, &user, Some(referrer.clone()));
+
+    assert_eq!(client.get_referrer(&user), Some(referrer.clone()));
+    assert_eq!(client.get_referrals(&referrer), vec![&env, user.clone()]);
+    assert!(client.try_claim_referral_reward(&user).is_err()); // Window still running
+
+    env.ledger().with_mut(|li| li.timestamp += 604_800);
+    assert!(client.try_claim_referral_reward(&user).is_err()); // Nothing to mint with yet
+    let bzr = create_bzr(&env, &client);
+    assert_eq!(client.claim_referral_reward(&user), 10);
+    assert_eq!(bzr.balance(&referrer), 10);
+    assert!(client.try_claim_referral_reward(&user).is_err());
+}
 
-    #[test]
-    fn test_lottery() {
-        let env = Env::default();
-        env.mock_all_auths();
-        let client = create_contract(&env);
-        let admin = Address::random(&env);
-        let user1 = Address::random(&env);
-        let user2 = Address::random(&env);
-        let target = Address::random(&env);
-
-        client.init(&admin);
-
-        client.stake(&user1, &None);
-        client.stake(&user2, &None);
-        client.stake(&target, &None);
-
-        // Fund users
-        for _ in 0..4 { client.vouch(&user1, &target); } // 20 BZR
-        for _ in 0..4 { client.vouch(&user2, &target); } // 20 BZR
-
-        client.buy_ticket(&user1);
-        client.buy_ticket(&user2);
-        
-        assert_eq!(client.get_lottery_info(), 2);
-        assert_eq!(client.get_balance(&user1), 10);
-
-        client.run_lottery();
-        
-        // One should have 10 (loser) + 0, one should have 10 (winner) + 20 = 30
-        let bal1 = client.get_balance(&user1);
-        let bal2 = client.get_balance(&user2);
-        
-        assert!( (bal1 == 30 && bal2 == 10) || (bal1 == 10 && bal2 == 30) );
-        assert_eq!(client.get_lottery_info(), 0);
+#[test]
+fn test_referral_cap_per_epoch() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let admin = Address::generate(&env);
+    let referrer = Address::generate(&env);
+
+    client.init(&admin);
+    let bond = setup_bond(&env, &client);
+    create_bzr(&env, &client);
+    stake(&client, &bond, &referrer, None);
+    let mut referees = Vec::new(&env);
+    for _ in 0..6 {
+        let user = Address::generate(&env);
+        stake(&client, &bond, &user, Some(referrer.clone()));
+        referees.push_back(user);
     }
 
-    #[test]
-    fn test_poverty_observation_window() {
-        let mut po = PovertyObservation {
-            start_time: 0,
-            is_active: false,
-        };
-
-        let current_time = 1000;
-        po.start_observation(current_time);
-        
-        // Check before 7 days (604800 seconds)
-        assert_eq!(po.verify_window(current_time + 604799), false);
-        assert_eq!(po.is_active, true);
-
-        // Check after 7 days
-        assert_eq!(po.verify_window(current_time + 604800), true);
-        assert_eq!(po.is_active, false);
-    }
+    env.ledger().with_mut(|li| li.timestamp += 604_800);
+    for i in 0..5 { client.claim_referral_reward(&referees.get(i).unwrap()); }
+    assert!(client.try_claim_referral_reward(&referees.get(5).unwrap()).is_err());
 
-    #[test]
-    fn test_poverty_observation_enforcement() {
-        let env = Env::default();
-        env.mock_all_auths();
-        
-        let client = create_contract(&env);
-        let user = Address::random(&env);
+    // The next epoch has room again
+    env.ledger().with_mut(|li| li.timestamp += 604_800);
+    client.claim_referral_reward(&referees.get(5).unwrap());
+    assert_eq!(client.get_supply_stats().minted_by_reason.get(MintReason::Referral), Some(60));
+}
 
-        // Set initial time
-        env.ledger().with_mut(|li| { li.timestamp = 1000; });
+fn listing(price: i128, burn: bool) -> BadgeListing {
+    BadgeListing { price, stock: None, duration: None, min_trust: None, burn }
+}
 
-        // Stake bond (starts 7-day timer)
-        client.stake(&user, &None);
+#[test]
+fn test_buy_badge_burns_or_pays_treasury() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let admin = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let user = Address::generate(&env);
+    let target = Address::generate(&env);
+
+    client.init(&admin);
+    let bond = setup_bond(&env, &client);
+    let bzr = create_bzr(&env, &client);
+    client.set_treasury(&treasury);
+    stake(&client, &bond, &user, None);
+    stake(&client, &bond, &target, None);
+    client.vouch(&user, &target); // Earn 5 BZR
+
+    let verified = symbol_short!("verified");
+    let vip = symbol_short!("vip");
+    client.list_badge(&verified, &listing(2, true));
+    client.list_badge(&vip, &BadgeListing { stock: Some(1), ..listing(3, false) });
+
+    client.buy_badge(&user, &verified);
+    client.buy_badge(&user, &vip);
+    assert_eq!(bzr.balance(&user), 0);
+    assert_eq!(bzr.balance(&treasury), 3);
+    assert_eq!(client.get_badges(&user), vec![&env, verified.clone(), vip.clone()]);
+    assert_eq!(client.get_badge_listing(&vip).unwrap().stock, Some(0));
+    assert!(client.try_buy_badge(&target, &vip).is_err()); // Sold out
+}
+
+#[test]
+fn test_badge_expiry_and_trust_requirement() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let target = Address::generate(&env);
+
+    client.init(&admin);
+    let bond = setup_bond(&env, &client);
+    create_bzr(&env, &client);
+    stake(&client, &bond, &user, None);
+    stake(&client, &bond, &target, None);
+    client.vouch(&user, &target);
+
+    let elite = symbol_short!("elite");
+    let promo = symbol_short!("promo");
+    client.list_badge(&elite, &BadgeListing { min_trust: Some(500), ..listing(1, true) });
+    client.list_badge(&promo, &BadgeListing { duration: Some(1000), ..listing(1, true) });
+
+    assert!(client.try_buy_badge(&user, &elite).is_err());
+    client.buy_badge(&user, &promo);
+    assert_eq!(client.has_badge(&user, &promo), true);
+
+    env.ledger().with_mut(|li| li.timestamp += 1000);
+    assert_eq!(client.has_badge(&user, &promo), false);
+    client.buy_badge(&user, &promo); // Renewing after expiry is allowed
+    assert_eq!(client.has_badge(&user, &promo), true);
+}
+
+fn fund(env: &Env, bzr: &bzr_token::BzrTokenClient, client: &TrustContractClient, to: &Address, amount: i128) {
+    env.as_contract(&client.address, || { bzr.mint(to, &amount); });
+}
+
+#[test]
+fn test_campaign_claimed_when_goal_met() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let admin = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    let backer1 = Address::generate(&env);
+    let backer2 = Address::generate(&env);
+
+    client.init(&admin);
+    let bond = setup_bond(&env, &client);
+    let bzr = create_bzr(&env, &client);
+    fund(&env, &bzr, &client, &backer1, 20);
+    fund(&env, &bzr, &client, &backer2, 20);
+    stake(&client, &bond, &merchant, None);
+
+    let id = client.create_campaign(&merchant, &35, &1000);
+    client.contribute(&backer1, &id, &15);
+    client.contribute(&backer2, &id, &10);
+    client.contribute(&backer1, &id, &5);
+    assert_eq!(client.get_contributors(&id), vec![&env, backer1.clone(), backer2.clone()]);
+    assert_eq!(client.get_contribution(&id, &backer1), 20);
+    assert!(client.try_claim_campaign(&id).is_err()); // 35 not reached yet
+
+    client.contribute(&backer2, &id, &5);
+    client.claim_campaign(&id);
+    assert_eq!(bzr.balance(&merchant), 35);
+    assert!(client.try_contribute(&backer2, &id, &1).is_err());
+}
+
+#[test]
+fn test_campaign_refunds_after_missed_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let admin = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    let backer = Address::generate(&env);
+
+    client.init(&admin);
+    let bond = setup_bond(&env, &client);
+    let bzr = create_bzr(&env, &client);
+    fund(&env, &bzr, &client, &backer, 20);
+    stake(&client, &bond, &merchant, None);
+
+    let id = client.create_campaign(&merchant, &100, &1000);
+    client.contribute(&backer, &id, &20);
+    assert!(client.try_refund(&backer, &id).is_err()); // Still running
+
+    env.ledger().with_mut(|li| li.timestamp += 1000);
+    client.refund(&backer, &id);
+    assert_eq!(bzr.balance(&backer), 20);
+    assert_eq!(client.get_campaign(&id).raised, 0);
+    assert!(client.try_refund(&backer, &id).is_err());
+    assert!(client.try_claim_campaign(&id).is_err());
+}
+
+#[test]
+fn test_single_witness_cannot_panic_default_circle() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let owner = Address::generate(&env);
+    let heir = Address::generate(&env);
+    let w1 = Address::generate(&env);
+    let pi = create_token(&env, &owner, 1_000);
+
+    client.create_vault(&owner, &vec![&env, HeirShare { heir: heir.clone(), share_bps: 10_000 }]);
+    client.deposit_to_vault(&owner, &pi.address, &1_000);
+    client.invite_witness(&owner, &w1);
+    client.invite_witness(&owner, &Address::generate(&env));
+    client.invite_witness(&owner, &Address::generate(&env));
+    client.accept_witness(&w1, &owner);
+
+    assert!(client.try_panic_button(&w1, &owner).is_err());
+    client.declare_emergency(&w1, &owner, &w1, &pi.address, &1_000);
+    assert!(client.try_witness_vote_medical(&w1, &owner).is_err());
+
+    env.ledger().with_mut(|li| { li.timestamp += 604_800; });
+    assert!(client.try_claim_legacy(&owner, &heir).is_err());
+    assert_eq!(pi.balance(&heir), 0);
+}
 
-        // Attempt verification immediately (Should Fail)
-        assert_eq!(client.verify_status(&user), false);
+#[test]
+fn test_witness_removal_is_delayed() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let owner = Address::generate(&env);
+    let witness = Address::generate(&env);
+    let thief = Address::generate(&env);
+
+    client.create_vault(&owner, &vec![&env, HeirShare { heir: Address::generate(&env), share_bps: 10_000 }]);
+    form_circle(&client, &owner, &vec![&env, witness.clone(), Address::generate(&env)]);
+    client.set_circle_config(&owner, &5, &1, &1);
+
+    // A stolen key proposes a new heir and tries to drop the witness first
+    client.update_heir(&owner, &vec![&env, HeirShare { heir: thief, share_bps: 10_000 }]);
+    client.remove_witness(&owner, &witness);
+    assert!(client.try_confirm_witness_removal(&owner, &witness).is_err());
+    assert_eq!(client.get_witnesses(&owner).len(), 2);
+
+    // The witness still sits in the circle and vetoes
+    client.veto_vault_change(&witness, &owner);
+    assert!(client.get_pending_vault_change(&owner).is_none());
+
+    env.ledger().with_mut(|li| { li.timestamp += 604_800; });
+    client.confirm_witness_removal(&owner, &witness);
+    assert_eq!(client.get_witnesses(&owner).len(), 1);
+    assert_eq!(client.get_pending_removals(&owner).len(), 0);
+}
 
-        // Warp time forward 7 days (604800 seconds) + 1 second
-        env.ledger().with_mut(|li| { li.timestamp = 1000 + 604800 + 1; });
+#[test]
+fn test_bond_floor_requires_bond_config() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let pi = create_token(&env, &user, 100);
+
+    client.init(&admin);
+    assert!(client.try_set_exile_config(&-20, &50).is_err());
+
+    assert!(client.try_stake(&user, &None).is_err()); // No bond asset yet
+    client.set_bond_config(&pi.address, &40, &2_592_000);
+    client.set_exile_config(&-20, &50);
+    client.stake(&user, &None); // 40 escrowed is short of the floor
+    assert_eq!(client.is_exiled(&user), true);
+}
 
-        // Attempt verification again (Should Pass)
-        assert_eq!(client.verify_status(&user), true);
+#[test]
+fn test_slash_runs_in_batches() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let admin = Address::generate(&env);
+    let scammer = Address::generate(&env);
+
+    client.init(&admin);
+    let bond = setup_bond(&env, &client);
+    stake(&client, &bond, &scammer, None);
+    let mut vouchers = Vec::new(&env);
+    for _ in 0..12 {
+        let voucher = Address::generate(&env);
+        stake(&client, &bond, &voucher, None);
+        client.vouch(&voucher, &scammer);
+        vouchers.push_back(voucher);
     }
+
+    client.raise_dispute(&admin, &scammer);
+    client.uphold_dispute(&scammer);
+    assert_eq!(client.is_slash_pending(&scammer), true);
+    assert_eq!(client.get_trust(&vouchers.get(9).unwrap()), 8);
+    assert_eq!(client.get_trust(&vouchers.get(10).unwrap()), 10);
+    assert!(client.try_revoke_vouch(&vouchers.get(11).unwrap(), &scammer).is_err());
+
+    client.continue_slash(&scammer);
+    assert_eq!(client.is_slash_pending(&scammer), false);
+    assert_eq!(client.get_trust(&vouchers.get(11).unwrap()), 8);
+    assert!(client.try_continue_slash(&scammer).is_err());
+}
+
+#[test]
+fn test_seal_query_applies_decay() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = create_contract(&env);
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    client.init(&admin);
+    client.set_decay_half_life(&1000);
+    env.as_contract(&client.address, || {
+        let mut merchant = new_merchant(&env, "User");
+        adjust_trust(&env, &user, &mut merchant, TRUST_CEILING);
+        env.storage().persistent().set(&DataKey::Merchant(user.clone()), &merchant);
+    });
+    assert_eq!(client.has_seal(&user), true);
+
+    env.ledger().with_mut(|li| li.timestamp += 5000);
+    assert_eq!(client.get_trust(&user), 64);
+    assert_eq!(client.has_seal(&user), false);
+    assert_eq!(client.get_badges(&user).len(), 0);
 }